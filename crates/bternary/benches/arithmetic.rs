@@ -0,0 +1,78 @@
+//! Baseline timings for the core Word operations.
+//!
+//! Run with `cargo bench -p bternary`, or `cargo bench -p bternary -- div_rem` for one group.
+//! Criterion keeps the previous run under `target/criterion` and reports the change against it.
+
+use std::hint::black_box;
+use bternary::Word;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Small, mid range and near the Word limits, positive and negative.
+const VALUES: [i64; 6] = [7, -364, 1_000_003, -98_765_432, 141_214_768_240, -141_214_768_239];
+
+fn operands() -> impl Iterator<Item = (i64, Word, Word)> {
+    VALUES.into_iter().map(|v| (v, Word::from_int(v), Word::from_int(-13)))
+}
+
+fn bench_add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+    for (v, a, b) in operands() {
+        group.bench_with_input(BenchmarkId::from_parameter(v), &(a, b), |bench, &(a, b)| {
+            bench.iter(|| black_box(a) + black_box(b))
+        });
+    }
+    group.finish();
+}
+
+fn bench_mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul");
+    for (v, a, b) in operands() {
+        group.bench_with_input(BenchmarkId::from_parameter(v), &(a, b), |bench, &(a, b)| {
+            bench.iter(|| black_box(a) * black_box(b))
+        });
+    }
+    group.finish();
+}
+
+fn bench_div_rem(c: &mut Criterion) {
+    let mut group = c.benchmark_group("div_rem");
+    for (v, a, b) in operands() {
+        group.bench_with_input(BenchmarkId::from_parameter(v), &(a, b), |bench, &(a, b)| {
+            bench.iter(|| black_box(a).checked_div_rem(black_box(b)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_from_int(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_int");
+    for v in VALUES {
+        group.bench_with_input(BenchmarkId::from_parameter(v), &v, |bench, &v| {
+            bench.iter(|| Word::from_int(black_box(v)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_to_int(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_int");
+    for (v, a, _) in operands() {
+        group.bench_with_input(BenchmarkId::from_parameter(v), &a, |bench, &a| {
+            bench.iter(|| black_box(a).to_int())
+        });
+    }
+    group.finish();
+}
+
+/// Converting a memory dump sized region, where `to_int` dominates.
+/// Compare the power table against the Horner loop by running this group with and without
+/// `--features simple-to-int`.
+fn bench_to_int_region(c: &mut Criterion) {
+    let words: Vec<Word> = (0..4096i64).map(|i| Word::from_int(i * 34_483_397 - 70_000_000_000)).collect();
+    c.bench_function("to_int_region", |bench| {
+        bench.iter(|| black_box(&words).iter().map(|w| w.to_int()).fold(0i64, i64::wrapping_add))
+    });
+}
+
+criterion_group!(benches, bench_add, bench_mul, bench_div_rem, bench_from_int, bench_to_int, bench_to_int_region);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Memory use and throughput of a large `Vec<Tryte>`.
+//!
+//! Compare the default one byte per trit storage against the packed one by running
+//! `cargo bench -p bternary --bench tryte` with and without `--features packed-tryte`.
+//! The buffer size is printed before the timings, Criterion has no memory measurement.
+
+use std::hint::black_box;
+use bternary::{Trit, Tryte};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+/// A million trytes, about the size of a large memory image.
+const COUNT: usize = 1_000_000;
+
+fn trytes() -> Vec<Tryte> {
+    (0..COUNT).map(|i| Tryte::from_int((i % 729) as i16 - 364)).collect()
+}
+
+fn bench_tryte_vec(c: &mut Criterion) {
+    let buffer = trytes();
+    println!(
+        "Vec<Tryte> of {} trytes: {} bytes, {} per tryte",
+        COUNT,
+        buffer.capacity() * size_of::<Tryte>(),
+        size_of::<Tryte>()
+    );
+
+    let mut group = c.benchmark_group("tryte_vec");
+    group.throughput(Throughput::Elements(COUNT as u64));
+    group.bench_function("build", |bench| bench.iter(trytes));
+    group.bench_function("to_int", |bench| {
+        bench.iter(|| black_box(&buffer).iter().map(|t| t.to_int() as i64).sum::<i64>())
+    });
+    group.bench_function("index", |bench| {
+        bench.iter(|| black_box(&buffer).iter().filter(|t| t[5] == Trit::Pos).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_tryte_vec);
+criterion_main!(benches);
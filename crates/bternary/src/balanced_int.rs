@@ -1,8 +1,42 @@
 use std::cmp::{Ordering, PartialOrd};
-use std::ops::Range;
 use thiserror::Error;
 use crate::balanced_int::BIntError::RangeInvalid;
-use crate::trit::Trit;
+use crate::trit::{Carry, InvalidTritValueError, Trit};
+
+/// `3^n`, usable in constants. Fails to evaluate (panics) above `n = 39`, where it leaves `i64`.
+pub const fn pow3(n: u32) -> i64 {
+    let mut result: i64 = 1;
+    let mut i = 0;
+    while i < n {
+        result *= 3;
+        i += 1;
+    }
+    result
+}
+
+/// The `(min, max)` values of `n` balanced trits, `max = (3^n - 1) / 2` and `min = -max`.
+/// Valid up to `n = 40` even though `pow3(40)` isn't, since the sum `1 + 3 + ... + 3^(n-1)`
+/// never leaves `i64`.
+pub const fn balanced_range(n: u32) -> (i64, i64) {
+    let mut max: i64 = 0;
+    let mut i = 0;
+    while i < n {
+        max = max * 3 + 1;
+        i += 1;
+    }
+    (-max, max)
+}
+
+/// `3^i` at index `i`, for every power below `3^40` that fits in `i64`.
+const POW3_TABLE: [i64; 40] = {
+    let mut table = [0; 40];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = pow3(i as u32);
+        i += 1;
+    }
+    table
+};
 
 pub trait Int:
 Copy
@@ -21,18 +55,36 @@ Copy
 + TryFrom<i64>
 + Ord
 {
+    /// The most trits whose balanced range `(3^N - 1) / 2` fits in this type.
+    const MAX_TRITS: usize;
+
     fn zero() -> Self;
     fn one() -> Self;
     fn two() -> Self;
     fn three() -> Self;
 }
 impl Int for i16 {
+    // (3^10 - 1) / 2 = 29524 <= i16::MAX < (3^11 - 1) / 2
+    const MAX_TRITS: usize = 10;
+
     fn zero() -> Self { 0 }
     fn one() -> Self { 1 }
     fn two() -> Self { 2 }
     fn three() -> Self { 3 }
 }
 impl Int for i64 {
+    // (3^40 - 1) / 2 ~ 6.1e18 <= i64::MAX < (3^41 - 1) / 2
+    const MAX_TRITS: usize = 40;
+
+    fn zero() -> Self { 0 }
+    fn one() -> Self { 1 }
+    fn two() -> Self { 2 }
+    fn three() -> Self { 3 }
+}
+impl Int for i128 {
+    // (3^80 - 1) / 2 ~ 7.4e37 <= i128::MAX < (3^81 - 1) / 2
+    const MAX_TRITS: usize = 80;
+
     fn zero() -> Self { 0 }
     fn one() -> Self { 1 }
     fn two() -> Self { 2 }
@@ -45,6 +97,9 @@ pub trait TernaryIntegerRepr {
     /// For example, for 6 trits (a tryte), we need at least i16 because 3^6 - 1 / 2 = 364.
     /// For 12 trits, we need at least i32 because 3^12 - 1 / 2 = 531440.
     /// For 20 trits, we need at least i64 because 3^20 - 1 / 2 = 17433922025.
+    /// Past 40 trits i64 runs out too and i128 takes over, up to 80 trits.
+    /// An `Int` that is too small fails to compile as soon as `to_int` or `from_int` is used,
+    /// since they check `N <= Int::MAX_TRITS` at compile time.
     type Int: Int;
 }
 
@@ -58,10 +113,34 @@ impl<const N: usize> BalancedInt<N> {
         BalancedInt([Trit::Zero; N])
     }
 
-    pub fn new(trits: [Trit; N]) -> Self {
+    pub const fn new(trits: [Trit; N]) -> Self {
         BalancedInt(trits)
     }
 
+    /// Builds a value from trits ordered most significant first, the reverse of `new`.
+    pub fn from_trits_msb_first(mut trits: [Trit; N]) -> Self {
+        trits.reverse();
+        BalancedInt(trits)
+    }
+
+    /// Builds from raw trit values least significant first, for reading external ternary data.
+    /// Errors with the first element that isn't -1, 0 or 1.
+    pub fn try_from_i8_array(values: [i8; N]) -> Result<Self, InvalidTritValueError> {
+        let mut trits = [Trit::Zero; N];
+        for (trit, value) in trits.iter_mut().zip(values) {
+            *trit = Trit::try_from(value)?;
+        }
+        Ok(BalancedInt(trits))
+    }
+
+    /// Returns the trits ordered most significant first.
+    /// Index 0 is least significant everywhere else in the crate.
+    pub fn to_trits_msb_first(&self) -> [Trit; N] {
+        let mut trits = self.0;
+        trits.reverse();
+        trits
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, Trit> {
         self.0.iter()
     }
@@ -70,6 +149,9 @@ impl<const N: usize> BalancedInt<N> {
         self.0.iter_mut()
     }
 
+    /// True when every trit is zero.
+    /// Balanced ternary has exactly one representation per value (there is no negative zero),
+    /// so this is the only zero and agrees with `is_numerically_zero`.
     pub fn is_zero(&self) -> bool {
         self.iter().all(|&t| t == Trit::Zero)
     }
@@ -82,6 +164,240 @@ impl<const N: usize> BalancedInt<N> {
         }
         Trit::Zero
     }
+
+    /// Checks in debug builds that every element is a legal trit value, and does nothing in
+    /// release builds. `Trit` is an enum so this can only fail after memory was reinterpreted
+    /// unsoundly, like a bad transmute from packed bytes, which is what it is meant to catch.
+    pub fn debug_assert_valid(&self) {
+        debug_assert!(
+            self.0.iter().all(|&t| (-1..=1).contains(&(t as i8))),
+            "invalid trit in {:?}",
+            self.0.map(|t| t as i8)
+        );
+    }
+
+    /// Negation only flips each trit, so it is available at any width.
+    pub fn negate(&self) -> Self {
+        BalancedInt(self.0.map(|t| t.negate()))
+    }
+
+    /// `negate` in place.
+    pub fn negate_assign(&mut self) {
+        for trit in self.iter_mut() {
+            *trit = trit.negate();
+        }
+        self.debug_assert_valid();
+    }
+
+    /// `abs` in place.
+    pub fn abs_assign(&mut self) {
+        if self.sign() == Trit::Neg {
+            self.negate_assign();
+        }
+        self.debug_assert_valid();
+    }
+
+    pub fn abs(&self) -> Self {
+        if self.sign() == Trit::Neg {
+            self.negate()
+        } else {
+            // copy that jawn its N bytes lol.
+            *self
+        }
+    }
+
+    /// `abs` along with whether it negated, which is exactly when the value is negative.
+    /// Zero gives `(zero, false)`.
+    pub fn abs_with_sign(&self) -> (Self, bool) {
+        let negative = self.sign() == Trit::Neg;
+        (if negative { self.negate() } else { *self }, negative)
+    }
+
+    /// Every value of this width in increasing order, from all `Neg` trits to all `Pos` trits.
+    /// That is `3^N` values, 729 for a Tryte but far too many to exhaust for a Word,
+    /// so wide types should be limited by the caller with `take`.
+    pub fn all_values() -> impl Iterator<Item = Self> {
+        std::iter::successors(Some(BalancedInt([Trit::Neg; N])), Self::successor)
+    }
+
+    /// The next larger value, `None` past the maximum.
+    fn successor(&self) -> Option<Self> {
+        let mut next = *self;
+        for trit in next.iter_mut() {
+            match trit {
+                Trit::Pos => *trit = Trit::Neg,
+                Trit::Zero => {
+                    *trit = Trit::Pos;
+                    return Some(next);
+                }
+                Trit::Neg => {
+                    *trit = Trit::Zero;
+                    return Some(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Index of the highest nonzero trit, `None` for zero.
+    pub fn most_significant_trit(&self) -> Option<usize> {
+        self.iter().rposition(|&t| t != Trit::Zero)
+    }
+
+    /// Floor of log base 3 of the absolute value, `None` for zero.
+    /// This is the index of the highest nonzero trit, minus one when the trits below it
+    /// pull the magnitude under that power of three (`8` is `10T`, just below `9`).
+    pub fn ilog3(&self) -> Option<u32> {
+        let magnitude = self.abs();
+        let msb = magnitude.most_significant_trit()?;
+        let below = magnitude.iter().take(msb).rev().find(|&&t| t != Trit::Zero);
+        let log = if below == Some(&Trit::Neg) { msb - 1 } else { msb };
+        Some(log as u32)
+    }
+
+    /// Majority vote over the trits: `Pos` if there are more `Pos` than `Neg` trits, `Neg` if
+    /// there are more `Neg`, and `Zero` when they tie. `Zero` trits abstain from the vote.
+    pub fn consensus(&self) -> Trit {
+        let balance: i64 = self.iter().map(|&t| t as i64).sum();
+        match balance.cmp(&0) {
+            Ordering::Greater => Trit::Pos,
+            Ordering::Less => Trit::Neg,
+            Ordering::Equal => Trit::Zero,
+        }
+    }
+
+    /// True when any trit is nonzero, the complement of `is_zero`.
+    pub fn any_nonzero(&self) -> bool {
+        !self.is_zero()
+    }
+
+    /// Trit wise equality mask: `Pos` where the trits of `self` and `other` are equal and `Neg`
+    /// where they differ. The mask never holds `Zero`, so it is not meant to be read as a number.
+    pub fn cmp_eq_mask(&self, other: &Self) -> Self {
+        BalancedInt(std::array::from_fn(|i| if self[i] == other[i] { Trit::Pos } else { Trit::Neg }))
+    }
+
+    /// Rotates the trits `amt` places toward the most significant end, the top trits wrapping
+    /// around to the bottom. `amt` is taken modulo `N`. See `wrapping_shift_left` for how this
+    /// differs from the other shifts.
+    pub fn rotate_left(&self, amt: usize) -> Self {
+        let mut trits = self.0;
+        if N > 0 {
+            trits.rotate_right(amt % N);
+        }
+        BalancedInt(trits)
+    }
+
+    /// Number of trit positions where `self` and `other` differ.
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        self.iter().zip(other.iter()).filter(|(a, b)| a != b).count()
+    }
+
+    /// Copies out the trits from `start` to `end` inclusive, least significant first, with the
+    /// same bounds checks as `read_trit_range`.
+    pub fn read_trits(&self, start: usize, end: usize) -> Result<Vec<Trit>, BIntError> {
+        if start > end || end >= N {
+            return Err(BIntError::RangeInvalid(start, end));
+        }
+        Ok(self.0[start..=end].to_vec())
+    }
+
+    /// The low `M` trits as an `M` trit value, zero filled when widening. Widening always keeps
+    /// the value, narrowing drops the high trits, which wraps the value like arithmetic does.
+    pub fn resize<const M: usize>(&self) -> BalancedInt<M> {
+        BalancedInt(std::array::from_fn(|i| if i < N { self.0[i] } else { Trit::Zero }))
+    }
+
+    /// `resize`, erroring with `ValueDoesNotFit` instead of dropping nonzero high trits.
+    pub fn try_resize<const M: usize>(&self) -> Result<BalancedInt<M>, BIntError> {
+        match self.sign_above(M) {
+            Trit::Zero => Ok(self.resize()),
+            _ => Err(BIntError::ValueDoesNotFit(0, M.saturating_sub(1))),
+        }
+    }
+
+    /// `resize`, clamping a value outside the `M` trit range to its every trit `Pos` maximum or
+    /// every trit `Neg` minimum. Widening and in range values are kept exactly.
+    pub fn to_narrower_saturating<const M: usize>(&self) -> BalancedInt<M> {
+        match self.sign_above(M) {
+            Trit::Zero => self.resize(),
+            sign => BalancedInt([sign; M]),
+        }
+    }
+
+    /// The most significant nonzero trit from trit `start` up, which is the sign of the whole
+    /// value whenever it is nonzero.
+    fn sign_above(&self, start: usize) -> Trit {
+        self.0.get(start..)
+            .and_then(|high| high.iter().rev().copied().find(|&t| t != Trit::Zero))
+            .unwrap_or(Trit::Zero)
+    }
+
+    /// Number of nonzero trits.
+    pub fn count_nonzero(&self) -> usize {
+        self.iter().filter(|&&t| t != Trit::Zero).count()
+    }
+
+    /// Reduces the trits to a single value, calling `f` with the accumulator, the trit's
+    /// position and the trit, from the least significant trit up.
+    pub fn fold_trits<B, F: FnMut(B, usize, Trit) -> B>(&self, init: B, mut f: F) -> B {
+        self.iter().enumerate().fold(init, |acc, (i, &t)| f(acc, i, t))
+    }
+
+    /// Run length encodes the trits, starting from the least significant trit.
+    /// Each `(trit, count)` pair is a run of `count` equal trits and adjacent runs always differ,
+    /// so small values are a couple of runs followed by one long run of zeros.
+    pub fn to_rle(&self) -> Vec<(Trit, u16)> {
+        let mut runs: Vec<(Trit, u16)> = Vec::new();
+        for &t in self.iter() {
+            match runs.last_mut() {
+                Some((trit, count)) if *trit == t => *count += 1,
+                _ => runs.push((t, 1)),
+            }
+        }
+        runs
+    }
+
+    /// Rebuilds a value from `to_rle` output.
+    /// Errors unless the run lengths add up to exactly `N` trits.
+    pub fn from_rle(runs: &[(Trit, u16)]) -> Result<Self, BIntError> {
+        let total: usize = runs.iter().map(|&(_, count)| count as usize).sum();
+        if total != N {
+            return Err(BIntError::RleLength(total, N));
+        }
+
+        let mut trits = [Trit::Zero; N];
+        let expanded = runs.iter().flat_map(|&(t, count)| std::iter::repeat_n(t, count as usize));
+        for (slot, t) in trits.iter_mut().zip(expanded) {
+            *slot = t;
+        }
+        Ok(BalancedInt(trits))
+    }
+
+    /// Converts to reflected ternary Gray code, where consecutive integers differ in exactly one trit.
+    /// Adding 1 to every trit gives the unbalanced digits of `value + (3^N - 1) / 2`, so this is the
+    /// standard reflected base 3 code. A digit is reflected (`2 - d`, which is negation for a trit)
+    /// when the sum of the higher digits is odd, which happens once per higher zero trit.
+    /// Reflection keeps zero as zero, so the code has the same zero trits as the input and the
+    /// transform is its own inverse.
+    pub fn to_gray(&self) -> Self {
+        let mut result = *self;
+        let mut reflect = false;
+        for i in (0..N).rev() {
+            if reflect {
+                result[i] = self[i].negate();
+            }
+            if self[i] == Trit::Zero {
+                reflect = !reflect;
+            }
+        }
+        result
+    }
+
+    /// Inverse of `to_gray`, which is `to_gray` itself.
+    pub fn from_gray(&self) -> Self {
+        self.to_gray()
+    }
 }
 
 impl<const N: usize> Default for BalancedInt<N> {
@@ -105,9 +421,102 @@ impl<const N: usize> From<[Trit; N]> for BalancedInt<N> {
 }
 
 impl<const N: usize> std::fmt::Display for BalancedInt<N> {
+    /// All `N` trits most significant first, e.g. `00011T` for a tryte holding 11.
+    ///
+    /// Given a width the leading zero trits are dropped instead and the rest padded like an
+    /// integer: right aligned with spaces by default, or with `0` trits under the `0` flag.
+    /// `format!("{:5}", tryte)` is `  11T` and `format!("{:05}", tryte)` is `0011T`.
+    /// The `+` flag only marks values above zero, a negative value already leads with `T`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let trit_strs: Vec<String> = self.iter().rev().map(|t| format!("{}", t)).collect();
-        write!(f, "{}", trit_strs.join(""))
+        let trits = self.iter().rev().map(|t| t.to_string());
+        if f.width().is_none() && !f.sign_plus() {
+            return f.write_str(&trits.collect::<String>());
+        }
+        let significant = self.most_significant_trit().map_or(1, |msb| msb + 1);
+        let digits: String = trits.skip(N - significant).collect();
+        if self.sign() != Trit::Neg || !f.sign_plus() {
+            return f.pad_integral(true, "", &digits);
+        }
+        // pad_integral would add a `+` or `-`, so pad by hand, right aligned like it does.
+        let padding = f.width().unwrap_or(0).saturating_sub(digits.len());
+        if f.sign_aware_zero_pad() {
+            return write!(f, "{}{digits}", "0".repeat(padding));
+        }
+        let (before, after) = match f.align() {
+            Some(std::fmt::Alignment::Left) => (0, padding),
+            Some(std::fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            _ => (padding, 0),
+        };
+        let fill = |count: usize| f.fill().to_string().repeat(count);
+        write!(f, "{}{digits}{}", fill(before), fill(after))
+    }
+}
+
+impl<const N: usize> BalancedInt<N> {
+    /// All `N` trits most significant first in the notation of the Setun machines, `+`, `0` and
+    /// `-` for `1`, `0` and `T`. A tryte holding 11 is `000++-` here and `00011T` in `Display`.
+    pub fn to_setun_string(&self) -> String {
+        self.iter()
+            .rev()
+            .map(|t| match t {
+                Trit::Neg => '-',
+                Trit::Zero => '0',
+                Trit::Pos => '+',
+            })
+            .collect()
+    }
+
+    /// Parses the `+`/`0`/`-` notation of `to_setun_string`, zero extending short strings like
+    /// `FromStr` does for the `1`/`0`/`T` notation.
+    pub fn from_setun_str(s: &str) -> Result<Self, ParseBalancedIntError> {
+        Self::parse_trits(s, |c| match c {
+            '-' => Some(Trit::Neg),
+            '0' => Some(Trit::Zero),
+            '+' => Some(Trit::Pos),
+            _ => None,
+        })
+    }
+
+    /// Parses a most significant first trit string, mapping each character with `trit`.
+    fn parse_trits(s: &str, trit: impl Fn(char) -> Option<Trit>) -> Result<Self, ParseBalancedIntError> {
+        if s.is_empty() {
+            return Err(ParseBalancedIntError::Empty);
+        }
+        let len = s.chars().count();
+        if len > N {
+            return Err(ParseBalancedIntError::TooLong(len, N));
+        }
+
+        let mut trits = [Trit::Zero; N];
+        for (slot, c) in trits.iter_mut().zip(s.chars().rev()) {
+            *slot = trit(c).ok_or(ParseBalancedIntError::InvalidTrit(c))?;
+        }
+        Ok(BalancedInt(trits))
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseBalancedIntError {
+    #[error("Cannot parse an empty trit string")]
+    Empty,
+    #[error("Invalid trit character '{0}'")]
+    InvalidTrit(char),
+    #[error("Trit string has {0} trits but only {1} fit")]
+    TooLong(usize, usize),
+}
+
+impl<const N: usize> std::str::FromStr for BalancedInt<N> {
+    type Err = ParseBalancedIntError;
+
+    /// Parses the same most significant first `T`/`0`/`1` notation that `Display` produces.
+    /// Strings shorter than `N` are zero extended.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_trits(s, |c| match c {
+            'T' => Some(Trit::Neg),
+            '0' => Some(Trit::Zero),
+            '1' => Some(Trit::Pos),
+            _ => None,
+        })
     }
 }
 
@@ -150,7 +559,53 @@ where
     Self: TernaryIntegerRepr,
     <Self as TernaryIntegerRepr>::Int: Int,
 {
+    /// The largest value, every trit `Pos`.
+    pub const MAX: Self = BalancedInt([Trit::Pos; N]);
+    /// The smallest value, every trit `Neg`.
+    pub const MIN: Self = BalancedInt([Trit::Neg; N]);
+
+    /// `(3^N - 1) / 2`, the integer value of `MAX`.
+    /// Not `const` since `Int` conversions are trait methods, see `balanced_range` for that.
+    pub fn max_value() -> <Self as TernaryIntegerRepr>::Int {
+        Self::MAX.to_int()
+    }
+
+    /// `-(3^N - 1) / 2`, the integer value of `MIN`.
+    pub fn min_value() -> <Self as TernaryIntegerRepr>::Int {
+        Self::MIN.to_int()
+    }
+
+    /// True when the value converts to integer zero, independent of how the trits compare.
+    /// Useful as a cross check of `is_zero` for values built through trit level APIs.
+    pub fn is_numerically_zero(&self) -> bool {
+        self.to_int() == <Self as TernaryIntegerRepr>::Int::zero()
+    }
+
+    /// Evaluated at compile time wherever it is referenced, failing the build for a mis-sized `Int`.
+    const INT_FITS: () = assert!(
+        N <= <<Self as TernaryIntegerRepr>::Int as Int>::MAX_TRITS,
+        "TernaryIntegerRepr::Int is too small to hold every value of this many trits"
+    );
+
+    /// The integer value, summing `trit * 3^i` from `POW3_TABLE`. Widths past the table, above
+    /// 40 trits, use the Horner loop instead, as does every width with the `simple-to-int`
+    /// feature, which exists to benchmark the two against each other. Both give the same value.
     pub fn to_int(self) -> <Self as TernaryIntegerRepr>::Int {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::INT_FITS;
+        if cfg!(feature = "simple-to-int") || N > POW3_TABLE.len() {
+            return self.to_int_horner();
+        }
+        let mut sum = 0i64;
+        for (&trit, power) in self.0.iter().zip(POW3_TABLE) {
+            sum += trit as i64 * power;
+        }
+        // At most (3^40 - 1) / 2 in size, and INT_FITS makes sure Int holds this width.
+        <Self as TernaryIntegerRepr>::Int::try_from(sum).unwrap_or_else(|_| unreachable!("value fits Int"))
+    }
+
+    /// `to_int` as one multiply and add per trit, from the most significant trit down.
+    fn to_int_horner(self) -> <Self as TernaryIntegerRepr>::Int {
         let mut acc = <Self as TernaryIntegerRepr>::Int::zero();
         for i in (0..N).rev() {
             acc *= <Self as TernaryIntegerRepr>::Int::three();
@@ -160,6 +615,8 @@ where
     }
 
     pub fn from_int(mut value: <Self as TernaryIntegerRepr>::Int) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::INT_FITS;
         let mut trits = [Trit::Zero; N];
 
         let zero = <Self as TernaryIntegerRepr>::Int::zero();
@@ -167,7 +624,7 @@ where
         let two = <Self as TernaryIntegerRepr>::Int::two();
         let three = <Self as TernaryIntegerRepr>::Int::three();
 
-        for i in 0..N {
+        for trit in trits.iter_mut() {
             if value == zero {
                 break; // The number is fully converted.
             }
@@ -184,7 +641,7 @@ where
                 value -= one;
             }
 
-            trits[i] = match rem {
+            *trit = match rem {
                 r if r == <Self as TernaryIntegerRepr>::Int::from(-1) => Trit::Neg,
                 r if r == zero => Trit::Zero,
                 r if r == one => Trit::Pos,
@@ -194,6 +651,17 @@ where
         BalancedInt(trits)
     }
 
+    /// `from_int` that errors instead of wrapping when `value` needs more than `N` trits.
+    pub fn from_int_checked(value: <Self as TernaryIntegerRepr>::Int) -> Result<Self, BIntError> {
+        let converted = Self::from_int(value);
+        // Wrapping drops the magnitude left over after N trits, which the round trip exposes.
+        if converted.to_int() == value {
+            Ok(converted)
+        } else {
+            Err(BIntError::ValueDoesNotFit(0, N - 1))
+        }
+    }
+
     /// reads a range of trits.
     /// TODO: Change this to use a slice method that is basically a reference span of start to end makes this better as its still doing TryFrom<i64> which means there is a limit.
     pub fn read_trit_range(&self, start: usize, end: usize) -> Result<<Self as TernaryIntegerRepr>::Int, BIntError>
@@ -207,104 +675,281 @@ where
                 value += self[i] as i64;
             }
             // Safely try to convert the i64 result into the requested type `T`
-            <Self as TernaryIntegerRepr>::Int::try_from(value).map_err(BIntError::ValueRange)
+            <Self as TernaryIntegerRepr>::Int::try_from(value).map_err(|_| BIntError::ValueDoesNotFit(start, end))
         }
     }
 
-    pub fn write_trit_range<T>(&mut self, value: T, start: usize, end: usize) -> Result<<Self as TernaryIntegerRepr>::Int, BIntError>
+    /// writes `value` into a range of trits, least significant trit at `start`.
+    /// Errors if the range is invalid or the value does not fit in `end - start + 1` trits.
+    pub fn write_trit_range<T>(&mut self, value: T, start: usize, end: usize) -> Result<(), BIntError>
     where
         T: Copy + TryInto<i64>,
     {
-        // --- Input Validation ---
-        assert!(start <= end, "Start of range cannot be after the end.");
-        assert!(end < N, "End of range is out of bounds for this Word size.");
+        if start > end || end >= N {
+            return Err(RangeInvalid(start, end));
+        }
 
-        let mut num = match value.try_into() {
-            Ok(n) => n,
-            Err(_) => return Err(),
-        };
+        let original: i64 = value.try_into().map_err(|_| BIntError::ValueDoesNotFit(start, end))?;
+        let mut num = original;
+        let mut trits = [Trit::Zero; N];
 
-        // --- Conversion and Writing Loop ---
         // Iterate from the least significant trit (start) to the most significant (end).
-        for i in start..=end {
-            let remainder = (num + 1) % 3 - 1;
-
-            self[i] = Trit::try_from(remainder as i8).unwrap();
-
-            num = (num - remainder) / 3;
+        for trit in trits.iter_mut().take(end + 1).skip(start) {
+            // Balance the remainder to be in the set {-1, 0, 1}
+            let quotient = num.div_euclid(3);
+            let (remainder, quotient) = match num.rem_euclid(3) {
+                2 => (-1, quotient + 1),
+                r => (r, quotient),
+            };
+            *trit = Trit::try_from(remainder as i8).unwrap();
+            num = quotient;
         }
 
         if num != 0 {
-            return Err("Value out of range for the given number of trits.");
+            return Err(BIntError::ValueDoesNotFit(start, end));
         }
 
+        // Only commit once we know the value fits so a failed write leaves self untouched.
+        self.0[start..=end].copy_from_slice(&trits[start..=end]);
+        self.debug_assert_valid();
         Ok(())
-    }}
+    }
 }
 
-#[derive(Error, Debug)]
+impl<const N: usize> TryFrom<i64> for BalancedInt<N>
+where
+    Self: TernaryIntegerRepr,
+{
+    type Error = BIntError;
+
+    /// Checked conversion, errors instead of wrapping when `value` needs more than `N` trits.
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        let mut result = Self::zero();
+        result.write_trit_range(value, 0, N - 1)?;
+        Ok(result)
+    }
+}
+
+impl<const N: usize> BalancedInt<N>
+where
+    Self: TernaryIntegerRepr<Int = i64>,
+{
+    /// `from_int` usable in `const` contexts, e.g. `const ONE: Word = Word::const_from_i64(1);`.
+    /// Wraps the same way `from_int` does.
+    pub const fn const_from_i64(value: i64) -> Self {
+        let mut trits = [Trit::Zero; N];
+        let mut num = value;
+        let mut i = 0;
+        while i < N {
+            let remainder = num.rem_euclid(3);
+            num = num.div_euclid(3);
+            trits[i] = match remainder {
+                0 => Trit::Zero,
+                1 => Trit::Pos,
+                _ => {
+                    num += 1;
+                    Trit::Neg
+                }
+            };
+            i += 1;
+        }
+        BalancedInt(trits)
+    }
+
+    /// How far `value` lies outside the range of `N` trits: positive past the max, negative
+    /// below the min, `None` when it fits. `Word::overflow_amount(141_214_768_241)` is `Some(1)`.
+    pub fn overflow_amount(value: i64) -> Option<i64> {
+        let (min, max) = balanced_range(N as u32);
+        if value > max {
+            Some(value - max)
+        } else if value < min {
+            Some(value - min)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BIntError {
     #[error("Invalid range {0}->{1}")]
     RangeInvalid(usize, usize),
     #[error("Cannot fit value in the trit range into the type requested.")]
     ValueRange(#[from] std::num::TryFromIntError),
-}
-
-impl<const N: usize> BalancedInt<N> {
-    /// Reads a range of trits and tries to convert them into a requested integer type `T`.
-    /// TODO: maybe just fix this entirely as it feels very weird.
+    #[error("Value does not fit in the trit range {0}->{1}")]
+    ValueDoesNotFit(usize, usize),
+    #[error("Run lengths add up to {0} trits but {1} are required")]
+    RleLength(usize, usize),
+    #[error("Slice lengths differ: {0} vs {1}")]
+    LengthMismatch(usize, usize),
+    #[error("{0} is not a base 3 digit")]
+    InvalidDigit(u8),
+    #[error("Negative value {0} has no unbalanced base 3 digits")]
+    NegativeUnbalanced(i64),
 }
 // Arthimetic operations.
 
 impl<const N: usize> BalancedInt<N> where Self: ArithmeticTernaryInteger {
-    pub fn full_add(&self, other: &Self, carry_in: Trit) -> (Self, Trit) {
-        let mut result = [Trit::Zero; N];
-        let mut carry = carry_in;
+    /// `self + other + carry_in`, returning the wrapped sum and the carry out of the top trit.
+    /// `carry_in` accepts a `Trit` as well as a `Carry`.
+    pub fn full_add(&self, other: &Self, carry_in: impl Into<Carry>) -> (Self, Carry) {
+        let mut result = *self;
+        let carry = result.add_assign_carry(*other, carry_in);
+        (result, carry)
+    }
 
+    /// In place version of `full_add`, returns the carry out of the most significant trit.
+    /// Feeding the carry into the next Word's `carry_in` chains additions across multiple Words.
+    pub fn add_assign_carry(&mut self, rhs: Self, carry_in: impl Into<Carry>) -> Carry {
+        let mut carry = carry_in.into().into_trit();
         for i in 0..N {
-            let (sum, new_carry) = self[i].full_add(other[i], carry);
-            result[i] = sum;
+            let (sum, new_carry) = self[i].full_add(rhs[i], carry);
+            self[i] = sum;
             carry = new_carry;
         }
-
-        (BalancedInt(result), carry)
+        Carry::from(carry)
     }
 
-    pub fn negate(&self) -> Self {
+    /// Left trit shift (multiply by 3)
+    /// Returns a new BalancedInt with all trits shifted left by `amt` positions.
+    /// See `wrapping_shift_left` for how this relates to the other shifts.
+    pub fn shift_left(&self, amt: usize) -> Self {
         let mut result = [Trit::Zero; N];
-        for i in 0..N {
-            result[i] = self.0[i].negate();
+        for i in amt..N {
+            result[i] = self[i - amt];
         }
         BalancedInt(result)
     }
 
-    /// Left trit shift (multiply by 3)
-    /// Returns a new BalancedInt with all trits shifted left by one position.
-    pub fn shift_left(&self, amt: usize) -> Self {
+    /// `self * 3^amt`, wrapping through the range the same way `Add` and `Mul` do.
+    ///
+    /// The three shift-like operations, for `amt` below `N`:
+    /// - `rotate_left` moves trits, the top `amt` trits coming back in at the bottom. It is a
+    ///   permutation of the trits with no arithmetic meaning.
+    /// - `shift_left` moves trits, the top `amt` trits falling off and zeros coming in.
+    /// - `wrapping_shift_left` multiplies by `3^amt` modulo `3^N`, landing back in
+    ///   `MIN..=MAX`. Dropping the top trits is exactly that reduction, so it gives the same
+    ///   trits as `shift_left`, this is the name to use when the arithmetic is what matters.
+    ///
+    /// Use `overflowing_shift_left` to detect the wrap instead.
+    pub fn wrapping_shift_left(&self, amt: usize) -> Self {
+        self.shift_left(amt)
+    }
+
+    /// The full `2N` trit product as `(low, high)`, so `self * rhs = low + high * 3^N`.
+    pub fn widening_mul(self, rhs: Self) -> (Self, Self) {
+        // Schoolbook multiplication summing each column before balancing it.
+        let mut columns = vec![0i64; 2 * N];
+        for (i, &a) in self.iter().enumerate() {
+            for (j, &b) in rhs.iter().enumerate() {
+                columns[i + j] += (a as i64) * (b as i64);
+            }
+        }
+        let mut carry = 0;
+        let (mut low, mut high) = (Self::zero(), Self::zero());
+        for (i, column) in columns.into_iter().enumerate() {
+            let sum = column + carry;
+            let mut digit = sum.rem_euclid(3);
+            carry = sum.div_euclid(3);
+            if digit == 2 {
+                digit = -1;
+                carry += 1;
+            }
+            let trit = Trit::try_from(digit as i8).expect("balanced digit");
+            if i < N { low[i] = trit } else { high[i - N] = trit }
+        }
+        (low, high)
+    }
+
+    /// `self * a + b` in one call.
+    /// Wrapping is reduction modulo `3^N`, so composing the wrapping `Mul` and `Add` already gives
+    /// the exact result reduced into range and a double width intermediate would change nothing.
+    /// Use `CheckedTernaryOps` to detect overflow instead.
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        self * a + b
+    }
+
+    /// Left trit shift that also reports whether a nonzero trit was shifted past position `N - 1`.
+    pub fn overflowing_shift_left(&self, amt: usize) -> (Self, bool) {
+        let lost = self.iter().skip(N.saturating_sub(amt)).any(|&t| t != Trit::Zero);
+        (self.shift_left(amt), lost)
+    }
+
+    /// Right trit shift, dropping the lowest `amt` trits.
+    /// Since the dropped trits are balanced this divides by `3^amt` rounding to the nearest
+    /// integer, not toward zero or negative infinity: `5 >> 1` is `2` and `-4 >> 1` is `-1`.
+    pub fn shift_right(&self, amt: usize) -> Self {
         let mut result = [Trit::Zero; N];
         for i in amt..N {
-            result[i] = self[i - amt];
+            result[i - amt] = self[i];
         }
         BalancedInt(result)
     }
 
+    /// Divides by `3^amt` rounding toward negative infinity, like `>>` on two's complement.
+    /// Differs from `shift_right` exactly when the dropped trits are negative, e.g. `-4` gives `-2`.
+    pub fn arithmetic_shift_right(&self, amt: usize) -> Self {
+        let quotient = self.shift_right(amt);
+        // The dropped trits hold the remainder, whose sign is its highest nonzero trit.
+        let remainder_sign = self.iter()
+            .take(amt.min(N))
+            .rev()
+            .find(|&&t| t != Trit::Zero)
+            .copied()
+            .unwrap_or(Trit::Zero);
+        if remainder_sign == Trit::Neg {
+            quotient - Self::from(Trit::Pos)
+        } else {
+            quotient
+        }
+    }
 
-    pub fn abs(&self) -> Self {
-        if self.sign() == Trit::Neg {
-            self.negate()
+    /// Division with remainder that returns `None` instead of panicking when `rhs` is zero.
+    pub fn checked_div_rem(self, rhs: Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            None
         } else {
-            // copy that jawn its N bytes lol.
-            *self
+            Some(self.div_rem(rhs))
+        }
+    }
+
+    /// `/` that returns `None` instead of panicking when `rhs` is zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(quotient, _)| quotient)
+    }
+
+    /// `%` that returns `None` instead of panicking when `rhs` is zero.
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        self.checked_div_rem(rhs).map(|(_, remainder)| remainder)
+    }
+
+    /// `div_rem` along with the long division's decision at each quotient position it visited,
+    /// as `(position, trit)` from the most significant position down. The trits are those of the
+    /// returned quotient, and positions that weren't visited are zero. Panics if `rhs` is zero.
+    pub fn div_rem_trace(self, rhs: Self) -> (Self, Self, Vec<(usize, Trit)>) {
+        let mut trace = Vec::new();
+        let (quotient, remainder) = self.div_rem_with(rhs, |i, trit| trace.push((i, trit)));
+        // Decisions are made against the divisor's magnitude, the quotient is negated after.
+        if rhs.sign() == Trit::Neg {
+            for (_, trit) in &mut trace {
+                *trit = -*trit;
+            }
         }
+        (quotient, remainder, trace)
     }
 
     /// Division with remainder.
     /// Returns (quotient, remainder)
     /// Panics if rhs is zero.
-    /// Uses a simple long-division algorithm.
+    fn div_rem(self, rhs: Self) -> (Self, Self) {
+        self.div_rem_with(rhs, |_, _| {})
+    }
+
+    /// Uses a simple long-division algorithm, calling `on_trit` with each quotient trit it
+    /// decides before the sign correction. A no-op `on_trit` compiles away.
     /// This is not optimized for performance.
     /// TODO: holy optimize, change it idk I just want something works for now.
-    fn div_rem(self, rhs: Self) -> (Self, Self) {
+    fn div_rem_with(self, rhs: Self, mut on_trit: impl FnMut(usize, Trit)) -> (Self, Self) {
         if rhs.is_zero() {
             panic!("Division by zero");
         }
@@ -316,35 +961,33 @@ impl<const N: usize> BalancedInt<N> where Self: ArithmeticTernaryInteger {
         // Work with a positive divisor to simplify the logic.
         let divisor_sign = rhs.sign();
         let divisor = rhs.abs();
-        let mut divisor_msb_pos = 0;
-        for i in (0..N).rev() {
-            if divisor[i] != Trit::Zero {
-                divisor_msb_pos = i;
-                break;
-            }
-        }
+        let divisor_msb_pos = divisor.most_significant_trit().expect("divisor is nonzero");
 
         for i in (0..(N - divisor_msb_pos)).rev() {
             let shifted_divisor = divisor.shift_left(i);
 
-            // Try subtracting the shifted divisor. If this makes the remainder's absolute value
-            // smaller, then the quotient trit is likely 1.
-            let rem_after_sub = remainder - shifted_divisor;
-            if rem_after_sub.abs() <= remainder.abs() {
-                remainder = rem_after_sub;
-                quotient[i] = Trit::Pos;
-                continue; // Move to the next lower trit position
+            // Only moving the remainder toward zero can shrink it, so a positive remainder only
+            // tries subtracting and a negative one only adding. Trying the other direction could
+            // also wrap around the range near the extremes and look like a smaller remainder.
+            match remainder.sign() {
+                Trit::Pos => {
+                    let rem_after_sub = remainder - shifted_divisor;
+                    if rem_after_sub.abs() <= remainder.abs() {
+                        remainder = rem_after_sub;
+                        quotient[i] = Trit::Pos;
+                    }
+                }
+                Trit::Neg => {
+                    let rem_after_add = remainder + shifted_divisor;
+                    if rem_after_add.abs() <= remainder.abs() {
+                        remainder = rem_after_add;
+                        quotient[i] = Trit::Neg;
+                    }
+                }
+                // Nothing left to divide, every remaining quotient trit is 0.
+                Trit::Zero => break,
             }
-
-            // Try adding the shifted divisor. If this makes the remainder's absolute value
-            // smaller, then the quotient trit is likely -1.
-            let rem_after_add = remainder + shifted_divisor;
-            if rem_after_add.abs() <= remainder.abs() {
-                remainder = rem_after_add;
-                quotient[i] = Trit::Neg;
-            }
-
-            // If neither operation reduced the remainder's magnitude, the quotient trit is 0.
+            on_trit(i, quotient[i]);
         }
 
         // Correct the quotient's sign based on the original divisor's sign
@@ -356,7 +999,51 @@ impl<const N: usize> BalancedInt<N> where Self: ArithmeticTernaryInteger {
     }
 }
 
-impl<const N: usize> std::ops::Neg for BalancedInt<N> where Self: ArithmeticTernaryInteger {
+/// Arithmetic that returns `None` instead of wrapping or panicking,
+/// so generic code needs a single bound for the whole operator set.
+pub trait CheckedTernaryOps: Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// `None` when `rhs` is zero.
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    /// Negation can't overflow in balanced ternary, so this always succeeds.
+    fn checked_neg(self) -> Option<Self>;
+    /// `None` when a nonzero trit would be shifted out.
+    fn checked_shl(self, amt: usize) -> Option<Self>;
+}
+
+impl<const N: usize> CheckedTernaryOps for BalancedInt<N> where Self: ArithmeticTernaryInteger {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        let (sum, carry) = self.full_add(&rhs, Carry::ZERO);
+        carry.is_zero().then_some(sum)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.negate())
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // The product fits when the upper half of the double width product is all zero.
+        let (low, high) = self.widening_mul(rhs);
+        high.is_zero().then_some(low)
+    }
+
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        BalancedInt::checked_div(self, rhs)
+    }
+
+    fn checked_neg(self) -> Option<Self> {
+        Some(self.negate())
+    }
+
+    fn checked_shl(self, amt: usize) -> Option<Self> {
+        let (shifted, overflowed) = self.overflowing_shift_left(amt);
+        (!overflowed).then_some(shifted)
+    }
+}
+
+impl<const N: usize> std::ops::Neg for BalancedInt<N> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
@@ -375,30 +1062,28 @@ impl<const N: usize> std::ops::Add for BalancedInt<N> where Self: ArithmeticTern
 
 impl<const N: usize> std::ops::AddAssign for BalancedInt<N> where Self: ArithmeticTernaryInteger {
     fn add_assign(&mut self, rhs: Self) {
-        let mut carry = Trit::Zero;
-        for i in 0..N {
-            let (sum, new_carry) = self[i].full_add(rhs[i], carry);
-            self[i] = sum;
-            carry = new_carry;
-        }
+        self.add_assign_carry(rhs, Trit::Zero);
     }
 }
 
 impl<const N: usize> std::ops::Sub for BalancedInt<N> where Self: ArithmeticTernaryInteger {
     type Output = Self;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn sub(self, rhs: Self) -> Self::Output {
         self + rhs.negate()
     }
 }
 
 impl<const N: usize> std::ops::SubAssign for BalancedInt<N> where Self: ArithmeticTernaryInteger {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn sub_assign(&mut self, rhs: Self) {
         *self += rhs.negate();
     }
 }
 
 impl<const N: usize> std::ops::MulAssign for BalancedInt<N> where Self: ArithmeticTernaryInteger {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn mul_assign(&mut self, rhs: Self) {
         // copy of self to use as multiplicand.
         let multiplicand = *self;
@@ -467,7 +1152,7 @@ impl<const N: usize> std::ops::RemAssign for BalancedInt<N> where Self: Arithmet
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Word;
+    use crate::{Tryte, Word};
 
     // Helper to quickly convert an i16 to a Tryte for testing.
     fn from_i64(val: i64) -> Word {
@@ -596,6 +1281,47 @@ mod tests {
         let _ = from_i64(10) / from_i64(0);
     }
 
+    #[test]
+    fn test_checked_division() {
+        assert_eq!(from_i64(10).checked_div(from_i64(3)), Some(from_i64(3)));
+        assert_eq!(from_i64(10).checked_rem(from_i64(3)), Some(from_i64(1)));
+        assert_eq!(from_i64(-10).checked_div_rem(from_i64(3)), Some((from_i64(-3), from_i64(-1))));
+        assert_eq!(from_i64(10).checked_div(Word::zero()), None);
+        assert_eq!(from_i64(10).checked_rem(Word::zero()), None);
+        assert_eq!(Word::zero().checked_div_rem(Word::zero()), None);
+    }
+
+    #[test]
+    fn test_div_rem_trace() {
+        for (p, d) in [(100, 7), (-100, 7), (100, -7), (364, 1), (5, 9), (-141_214_768_240, 3)] {
+            let (quotient, remainder, trace) = from_i64(p).div_rem_trace(from_i64(d));
+            assert_eq!(from_i64(p).checked_div_rem(from_i64(d)), Some((quotient, remainder)));
+
+            let mut rebuilt = Word::zero();
+            for &(i, trit) in &trace {
+                rebuilt[i] = trit;
+            }
+            assert_eq!(rebuilt, quotient, "{p} / {d}");
+            assert!(trace.windows(2).all(|w| w[0].0 == w[1].0 + 1));
+        }
+        // 100 = 7 * 14 + 2 and 14 is 1TTT, the trace starts from the top position 7 can move to.
+        let (_, _, trace) = from_i64(100).div_rem_trace(from_i64(7));
+        assert_eq!(trace[0].0, 21);
+        assert_eq!(&trace[trace.len() - 4..], [(3, Trit::Pos), (2, Trit::Neg), (1, Trit::Neg), (0, Trit::Neg)]);
+    }
+
+    #[test]
+    fn test_division_near_extremes() {
+        let max = 141_214_768_240i64;
+        assert_eq!(from_i64(-max).checked_div_rem(from_i64(1)), Some((from_i64(-max), Word::zero())));
+        assert_eq!(from_i64(max).checked_div_rem(from_i64(-1)), Some((from_i64(-max), Word::zero())));
+        for (p, d) in [(-100_000_000_000, 31_381_059_609), (max, 3), (-max, 94_143_178_827), (max - 7, max), (123, -max)] {
+            let (q, r) = from_i64(p).checked_div_rem(from_i64(d)).unwrap();
+            assert_eq!(q.to_int() * d + r.to_int(), p, "{p} / {d}");
+            assert!(2 * r.to_int().abs() <= d.abs(), "{p} / {d} leaves {}", r.to_int());
+        }
+    }
+
     #[test]
     fn test_remainder() {
         // Balanced ternary remainder is always between -rhs/2 and +rhs/2
@@ -618,10 +1344,572 @@ mod tests {
         assert!(from_i64(300).shift_left(30).is_zero());
     }
 
+    #[test]
+    fn test_from_str() {
+        assert_eq!("1T0".parse::<Word>().unwrap().to_int(), 6);
+        assert_eq!("TTT".parse::<Word>().unwrap().to_int(), -13);
+        assert_eq!(from_i64(-123456).to_string().parse::<Word>().unwrap(), from_i64(-123456));
+        assert_eq!("".parse::<Word>(), Err(ParseBalancedIntError::Empty));
+        assert_eq!("1T2".parse::<Word>(), Err(ParseBalancedIntError::InvalidTrit('2')));
+        assert_eq!("1".repeat(25).parse::<Word>(), Err(ParseBalancedIntError::TooLong(25, 24)));
+    }
+
+    #[test]
+    fn test_trit_range_round_trip() {
+        let mut word = Word::zero();
+        word.write_trit_range(-200, 6, 11).unwrap();
+        word.write_trit_range(7, 0, 5).unwrap();
+        assert_eq!(word.read_trit_range(6, 11).unwrap(), -200);
+        assert_eq!(word.read_trit_range(0, 5).unwrap(), 7);
+        // 365 does not fit in 6 trits and must leave the word untouched.
+        assert_eq!(word.write_trit_range(365, 0, 5), Err(BIntError::ValueDoesNotFit(0, 5)));
+        assert_eq!(word.read_trit_range(0, 5).unwrap(), 7);
+        assert_eq!(word.read_trit_range(5, 24), Err(BIntError::RangeInvalid(5, 24)));
+    }
+
+    #[test]
+    fn test_msb_first_ordering() {
+        let value = from_i64(-98_765);
+        let msb_first = value.to_trits_msb_first();
+        let lsb_first: Vec<Trit> = value.iter().copied().collect();
+        assert!(msb_first.iter().eq(lsb_first.iter().rev()));
+
+        assert_eq!(Word::from_trits_msb_first(msb_first), value);
+        assert_eq!(Word::from_trits_msb_first(msb_first).to_int(), -98_765);
+        assert_ne!(Word::new(msb_first).to_int(), -98_765);
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(from_i64(0).hamming_distance(&from_i64(0)), 0);
+        assert_eq!(from_i64(1).hamming_distance(&from_i64(-1)), 1);
+        // 4 = 11, 5 = 1TT
+        assert_eq!(from_i64(4).hamming_distance(&from_i64(5)), 3);
+    }
+
+    #[test]
+    fn test_setun_notation() {
+        assert_eq!(Tryte::from_int(11).to_setun_string(), "000++-");
+        assert_eq!(Tryte::from_int(-11).to_setun_string(), "000--+");
+        assert_eq!(Tryte::from_setun_str("++-"), Ok(Tryte::from_int(11)));
+        for value in [0, 1, -1, 11, 364, -364] {
+            let tryte = Tryte::from_int(value);
+            assert_eq!(Tryte::from_setun_str(&tryte.to_setun_string()), Ok(tryte));
+        }
+        for value in [141_214_768_240, -141_214_768_240, 123_456_789] {
+            let word = from_i64(value);
+            assert_eq!(Word::from_setun_str(&word.to_setun_string()).unwrap().to_int(), value);
+        }
+        assert_eq!(Tryte::from_setun_str("1T"), Err(ParseBalancedIntError::InvalidTrit('T')));
+        assert_eq!(Tryte::from_setun_str(""), Err(ParseBalancedIntError::Empty));
+        assert_eq!(Tryte::from_setun_str("+++++++"), Err(ParseBalancedIntError::TooLong(7, 6)));
+    }
+
+    #[test]
+    fn test_read_trits() {
+        // 11 = 11T
+        let word = from_i64(11);
+        assert_eq!(word.read_trits(0, 2), Ok(vec![Trit::Neg, Trit::Pos, Trit::Pos]));
+        assert_eq!(word.read_trits(1, 1), Ok(vec![Trit::Pos]));
+        assert_eq!(word.read_trits(0, 23).unwrap(), word.iter().copied().collect::<Vec<_>>());
+        assert_eq!(word.read_trits(20, 24), Err(BIntError::RangeInvalid(20, 24)));
+        assert_eq!(word.read_trits(3, 2), Err(BIntError::RangeInvalid(3, 2)));
+    }
+
+    #[test]
+    fn test_to_int_table_matches_horner() {
+        use crate::{LongWord, Tryte};
+
+        for value in -364..=364 {
+            let tryte = Tryte::from_int(value);
+            assert_eq!(tryte.to_int(), value);
+            assert_eq!(tryte.to_int_horner(), value);
+        }
+        for value in [0, 1, -2, 1_000_003, 141_214_768_240, -141_214_768_240] {
+            assert_eq!(from_i64(value).to_int(), from_i64(value).to_int_horner());
+        }
+        assert_eq!(LongWord::MAX.to_int(), LongWord::MAX.to_int_horner());
+        assert_eq!(LongWord::MIN.to_int(), -6_078_832_729_528_464_400);
+    }
+
+    #[test]
+    fn test_resize() {
+        let word = from_i64(-1000);
+        assert_eq!(word.resize::<40>().to_int(), -1000);
+        assert_eq!(word.resize::<40>().resize::<24>(), word);
+        // -1000 is TT0T00T, dropping the top trit leaves T0T00T = -1000 + 729.
+        assert_eq!(word.resize::<6>().to_int(), -271);
+    }
+
+    #[test]
+    fn test_narrowing_behaviors() {
+        use crate::Tryte;
+
+        let inside = from_i64(-364);
+        assert_eq!(inside.to_narrower_saturating::<6>(), Tryte::from_int(-364));
+        assert_eq!(inside.try_resize::<6>().map(BalancedInt::to_int), Ok(-364));
+        for (value, saturated) in [(1000, 364), (-1000, -364), (365, 364), (-141_214_768_240, -364)] {
+            let word = from_i64(value);
+            assert_eq!(word.to_narrower_saturating::<6>(), Tryte::from_int(saturated));
+            assert_eq!(word.try_resize::<6>(), Err(BIntError::ValueDoesNotFit(0, 5)));
+        }
+        // 365 is 1TTTTTT, truncating keeps the low six trits, -364.
+        assert_eq!(from_i64(365).resize::<6>(), Tryte::MIN);
+        assert_eq!(from_i64(1000).to_narrower_saturating::<40>().to_int(), 1000);
+    }
+
+    #[test]
+    fn test_fold_trits() {
+        for value in [0, 1, -1, 4, 5, 364, -141_214_768_240] {
+            let word = from_i64(value);
+            let count = word.fold_trits(0, |n, _, t| n + usize::from(t != Trit::Zero));
+            assert_eq!(count, word.count_nonzero());
+            // Folding the weighted trits back up gives the value.
+            assert_eq!(word.fold_trits(0i64, |acc, i, t| acc + t as i64 * pow3(i as u32)), value);
+        }
+        assert_eq!(from_i64(5).count_nonzero(), 3);
+        let positions = from_i64(10).fold_trits(Vec::new(), |mut v, i, t| {
+            if t != Trit::Zero {
+                v.push(i);
+            }
+            v
+        });
+        assert_eq!(positions, [0, 2]);
+    }
+
+    #[test]
+    fn test_wrapping_shift_left() {
+        // 3 * MAX is 3^24 past MAX - 1.
+        assert_eq!(Word::MAX.wrapping_shift_left(1).to_int(), 141_214_768_239);
+        assert_eq!(Word::MAX.wrapping_shift_left(1), Word::MAX + Word::MAX + Word::MAX);
+        let near_max = from_i64(141_214_768_000);
+        assert_eq!(near_max.wrapping_shift_left(1).to_int(), 3 * 141_214_768_000 - 3i64.pow(24));
+        assert_eq!(near_max.wrapping_shift_left(2), near_max * from_i64(9));
+        assert_eq!(from_i64(-5).wrapping_shift_left(2).to_int(), -45);
+        // Same trits as shift_left, unlike rotate_left which brings the top trit back around.
+        assert_eq!(Word::MAX.wrapping_shift_left(1), Word::MAX.shift_left(1));
+        assert_eq!(Word::MAX.rotate_left(1), Word::MAX);
+    }
+
+    #[test]
+    fn test_rotate_left() {
+        let value = Tryte::from_int(1);
+        assert_eq!(value.rotate_left(2).to_int(), 9);
+        assert_eq!(value.rotate_left(6), value);
+        assert_eq!(value.rotate_left(8), value.rotate_left(2));
+        // 243 = 100000, the top trit wraps to trit 0.
+        assert_eq!(Tryte::from_int(-243).rotate_left(1).to_int(), -1);
+    }
+
+    #[test]
+    fn test_gray_code() {
+        for i in -1000..1000 {
+            let gray = from_i64(i).to_gray();
+            let next = from_i64(i + 1).to_gray();
+            assert_eq!(gray.hamming_distance(&next), 1, "Gray codes of {} and {} differ in more than one trit", i, i + 1);
+            assert_eq!(gray.from_gray().to_int(), i);
+        }
+    }
+
+    #[test]
+    fn test_overflowing_shift_left() {
+        assert_eq!(from_i64(10).overflowing_shift_left(1), (from_i64(30), false));
+        assert_eq!(from_i64(10).overflowing_shift_left(0), (from_i64(10), false));
+        // 10 is 101, so its top trit lands exactly on position 23 with a shift of 21.
+        assert!(!from_i64(10).overflowing_shift_left(21).1);
+        assert!(from_i64(10).overflowing_shift_left(22).1);
+        assert_eq!(from_i64(-300).overflowing_shift_left(30), (Word::zero(), true));
+        assert_eq!(from_i64(0).overflowing_shift_left(30), (Word::zero(), false));
+    }
+
+    #[test]
+    fn test_rle_sparse() {
+        let sparse = from_i64(-4); // TT
+        let runs = sparse.to_rle();
+        assert_eq!(runs, vec![(Trit::Neg, 2), (Trit::Zero, 22)]);
+        assert_eq!(Word::from_rle(&runs).unwrap(), sparse);
+        assert_eq!(Word::zero().to_rle(), vec![(Trit::Zero, 24)]);
+    }
+
+    #[test]
+    fn test_rle_dense() {
+        let dense = Word::new([Trit::Pos, Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Zero,
+            Trit::Pos, Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Zero,
+            Trit::Pos, Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Zero,
+            Trit::Pos, Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Zero]);
+        let runs = dense.to_rle();
+        assert_eq!(runs.len(), 24);
+        assert_eq!(Word::from_rle(&runs).unwrap(), dense);
+    }
+
+    #[test]
+    fn test_rle_length_validation() {
+        assert_eq!(Word::from_rle(&[(Trit::Pos, 23)]), Err(BIntError::RleLength(23, 24)));
+        assert_eq!(Word::from_rle(&[(Trit::Pos, 20), (Trit::Neg, 5)]), Err(BIntError::RleLength(25, 24)));
+    }
+
+    #[test]
+    fn test_add_assign_carry_chain() {
+        // 48 trit numbers stored as [low, high] Words.
+        // a = max + 1 * 3^24 and b = 1 + 2 * 3^24, so the low Words carry into the high ones.
+        let max = 141_214_768_240;
+        let mut acc = [from_i64(max), from_i64(1)];
+        let rhs = [from_i64(1), from_i64(2)];
+
+        let mut carry = Carry::ZERO;
+        for (limb, &other) in acc.iter_mut().zip(rhs.iter()) {
+            carry = limb.add_assign_carry(other, carry);
+        }
+
+        assert_eq!(carry, Trit::Zero);
+        // low: max + 1 wraps to -max with a carry of 1 into the high Word.
+        assert_eq!(acc[0].to_int(), -max);
+        assert_eq!(acc[1].to_int(), 4);
+
+        let mut sum = from_i64(-max);
+        assert_eq!(sum.add_assign_carry(from_i64(-1), Trit::Zero), Trit::Neg);
+        assert_eq!(sum.to_int(), max);
+    }
+
+    #[test]
+    fn test_zero_is_canonical() {
+        assert!(Word::zero().is_numerically_zero());
+        assert!(!from_i64(1).is_numerically_zero());
+
+        for i in (-5000..5000).step_by(7) {
+            let a = from_i64(i);
+            let results = [a - a, a + -a, a * Word::zero(), Word::zero() * a, -a + a];
+            for result in results {
+                assert!(result.is_numerically_zero());
+                assert!(result.is_zero(), "non canonical zero {} from {}", result, i);
+                assert_eq!(result, Word::zero());
+            }
+            if i != 0 {
+                assert_eq!(a % a, Word::zero());
+                assert_eq!(Word::zero() / a, Word::zero());
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_from_i8_array() {
+        let tryte = Tryte::try_from_i8_array([1, 0, -1, 0, 0, 1]).unwrap();
+        assert_eq!(tryte.to_int(), 1 - 9 + 243);
+        assert_eq!(
+            Tryte::try_from_i8_array([1, 0, 2, 0, 0, 1]),
+            Err(InvalidTritValueError(2))
+        );
+    }
+
+    #[test]
+    fn test_shift_right_rounds_to_nearest() {
+        assert_eq!(from_i64(27).shift_right(2).to_int(), 3);
+        assert_eq!(from_i64(5).shift_right(1).to_int(), 2);
+        assert_eq!(from_i64(-4).shift_right(1).to_int(), -1);
+        assert_eq!(from_i64(-5).shift_right(1).to_int(), -2);
+        assert!(from_i64(-5).shift_right(24).is_zero());
+    }
+
+    #[test]
+    fn test_arithmetic_shift_right_floors() {
+        for value in -200i64..=200 {
+            for amt in 0..=6 {
+                let expected = value.div_euclid(3i64.pow(amt as u32));
+                assert_eq!(
+                    from_i64(value).arithmetic_shift_right(amt).to_int(),
+                    expected,
+                    "{value} asr {amt}"
+                );
+            }
+        }
+        assert_eq!(from_i64(-5).arithmetic_shift_right(1).to_int(), -2);
+        // Floor and nearest disagree here.
+        assert_eq!(from_i64(-4).arithmetic_shift_right(1).to_int(), -2);
+        assert_eq!(from_i64(5).arithmetic_shift_right(1).to_int(), 1);
+        assert_eq!(from_i64(-1).arithmetic_shift_right(30).to_int(), -1);
+        assert!(from_i64(1).arithmetic_shift_right(30).is_zero());
+    }
+
+    const TABLE: [Word; 5] = [
+        Word::const_from_i64(0),
+        Word::const_from_i64(1),
+        Word::const_from_i64(-13),
+        Word::const_from_i64(141_214_768_240),
+        Word::const_from_i64(i64::MIN),
+    ];
+
+    #[test]
+    fn test_const_from_i64_matches_from_int() {
+        let values = [0, 1, -13, 141_214_768_240, i64::MIN];
+        for (word, value) in TABLE.iter().zip(values) {
+            assert_eq!(*word, Word::from_int(value));
+        }
+    }
+
+    #[test]
+    fn test_ilog3() {
+        assert_eq!(from_i64(1).ilog3(), Some(0));
+        assert_eq!(from_i64(2).ilog3(), Some(0));
+        assert_eq!(from_i64(3).ilog3(), Some(1));
+        assert_eq!(from_i64(8).ilog3(), Some(1));
+        assert_eq!(from_i64(9).ilog3(), Some(2));
+        assert_eq!(from_i64(0).ilog3(), None);
+        for value in 1i64..2000 {
+            assert_eq!(from_i64(value).ilog3(), Some(value.ilog(3)), "{value}");
+            assert_eq!(from_i64(-value).ilog3(), Some(value.ilog(3)), "-{value}");
+        }
+    }
+
+    #[test]
+    fn test_cmp_eq_mask() {
+        let a = Tryte::new([Trit::Pos, Trit::Zero, Trit::Neg, Trit::Pos, Trit::Zero, Trit::Neg]);
+        let b = Tryte::new([Trit::Pos, Trit::Pos, Trit::Neg, Trit::Neg, Trit::Zero, Trit::Zero]);
+        assert_eq!(
+            a.cmp_eq_mask(&b),
+            Tryte::new([Trit::Pos, Trit::Neg, Trit::Pos, Trit::Neg, Trit::Pos, Trit::Neg])
+        );
+        assert_eq!(a.cmp_eq_mask(&a), Tryte::new([Trit::Pos; 6]));
+        assert_eq!(a.cmp_eq_mask(&b).iter().filter(|&&t| t == Trit::Neg).count(), a.hamming_distance(&b));
+    }
+
+    #[test]
+    fn test_consensus() {
+        assert_eq!(Word::new([Trit::Pos; 24]).consensus(), Trit::Pos);
+        assert_eq!(Word::new([Trit::Neg; 24]).consensus(), Trit::Neg);
+        assert_eq!(Word::zero().consensus(), Trit::Zero);
+        // 1T1 with zeros above: two Pos against one Neg, the zeros abstain.
+        assert_eq!(from_i64(7).consensus(), Trit::Pos);
+        // 1T is a tie even though the value is positive.
+        assert_eq!(from_i64(2).consensus(), Trit::Zero);
+        assert_eq!(from_i64(-2).consensus(), Trit::Zero);
+        // T11 is negative but outvoted.
+        assert_eq!(from_i64(-5).consensus(), Trit::Pos);
+    }
+
+    #[test]
+    fn test_any_nonzero() {
+        assert!(!Word::zero().any_nonzero());
+        assert!(from_i64(-1).any_nonzero());
+        assert!(Word::new([Trit::Pos; 24]).any_nonzero());
+    }
+
+    fn checked_sum<T: CheckedTernaryOps + Copy>(values: &[T]) -> Option<T> {
+        let (first, rest) = values.split_first()?;
+        rest.iter().try_fold(*first, |acc, &v| acc.checked_add(v))
+    }
+
+    #[test]
+    fn test_checked_ternary_ops() {
+        let max = Word::new([Trit::Pos; 24]);
+        let min = Word::new([Trit::Neg; 24]);
+        let one = from_i64(1);
+
+        assert_eq!(CheckedTernaryOps::checked_add(from_i64(40), from_i64(2)), Some(from_i64(42)));
+        assert_eq!(CheckedTernaryOps::checked_add(max, one), None);
+        assert_eq!(CheckedTernaryOps::checked_sub(from_i64(-40), from_i64(2)), Some(from_i64(-42)));
+        assert_eq!(CheckedTernaryOps::checked_sub(min, one), None);
+
+        assert_eq!(CheckedTernaryOps::checked_mul(from_i64(-6), from_i64(7)), Some(from_i64(-42)));
+        assert_eq!(CheckedTernaryOps::checked_mul(max, from_i64(2)), None);
+        assert_eq!(CheckedTernaryOps::checked_mul(from_i64(376_000), from_i64(376_000)), None);
+        assert_eq!(CheckedTernaryOps::checked_mul(min, one), Some(min));
+        assert_eq!(CheckedTernaryOps::checked_mul(max, Word::zero()), Some(Word::zero()));
+
+        assert_eq!(CheckedTernaryOps::checked_div(from_i64(42), from_i64(-6)), Some(from_i64(-7)));
+        assert_eq!(CheckedTernaryOps::checked_div(from_i64(42), Word::zero()), None);
+        assert_eq!(CheckedTernaryOps::checked_neg(min), Some(max));
+        assert_eq!(CheckedTernaryOps::checked_shl(from_i64(5), 2), Some(from_i64(45)));
+        assert_eq!(CheckedTernaryOps::checked_shl(max, 1), None);
+
+        assert_eq!(checked_sum(&[from_i64(1), from_i64(2), from_i64(3)]), Some(from_i64(6)));
+        assert_eq!(checked_sum(&[max, one]), None);
+    }
+
+    #[test]
+    fn test_max_min() {
+        assert_eq!(Word::MAX.to_int(), 141_214_768_240);
+        assert_eq!(Word::MIN.to_int(), -141_214_768_240);
+        assert_eq!(Tryte::MAX.to_int(), 364);
+        assert_eq!(Tryte::MIN.to_int(), -364);
+        assert_eq!(Word::max_value(), balanced_range(24).1);
+        assert_eq!(Word::min_value(), balanced_range(24).0);
+        assert_eq!(Tryte::max_value(), 364i16);
+        assert_eq!(Tryte::min_value(), -364i16);
+        assert_eq!(Word::MIN, -Word::MAX);
+        assert_eq!(Word::MAX + Word::from_int(1), Word::MIN);
+    }
+
+    #[test]
+    fn test_from_int_checked() {
+        assert_eq!(Word::from_int_checked(141_214_768_240).unwrap().to_int(), 141_214_768_240);
+        assert_eq!(Word::from_int_checked(-141_214_768_240).unwrap().to_int(), -141_214_768_240);
+        assert_eq!(Word::from_int_checked(141_214_768_241), Err(BIntError::ValueDoesNotFit(0, 23)));
+        assert_eq!(Word::from_int_checked(-141_214_768_241), Err(BIntError::ValueDoesNotFit(0, 23)));
+        assert!(Word::from_int_checked(i64::MAX).is_err());
+        assert_eq!(Tryte::from_int_checked(364).unwrap().to_int(), 364);
+        assert!(Tryte::from_int_checked(365).is_err());
+        assert!(Tryte::from_int_checked(i16::MIN).is_err());
+    }
+
+    #[test]
+    fn test_overflow_amount() {
+        assert_eq!(Word::overflow_amount(0), None);
+        assert_eq!(Word::overflow_amount(141_214_768_240), None);
+        assert_eq!(Word::overflow_amount(-141_214_768_240), None);
+        assert_eq!(Word::overflow_amount(141_214_768_241), Some(1));
+        assert_eq!(Word::overflow_amount(-141_214_768_241), Some(-1));
+        assert_eq!(Word::overflow_amount(i64::MIN), Some(i64::MIN + 141_214_768_240));
+    }
+
+    #[test]
+    fn test_pow3_and_balanced_range() {
+        assert_eq!(pow3(0), 1);
+        assert_eq!(pow3(6), 729);
+        assert_eq!(pow3(39), 4_052_555_153_018_976_267);
+        assert_eq!(balanced_range(0), (0, 0));
+        assert_eq!(balanced_range(1), (-1, 1));
+        assert_eq!(balanced_range(6), (-364, 364));
+        assert_eq!(balanced_range(24), (-141_214_768_240, 141_214_768_240));
+        assert_eq!(balanced_range(40).1 as i128, (3i128.pow(40) - 1) / 2);
+        for n in 1..=39 {
+            assert_eq!(balanced_range(n).1, (pow3(n) - 1) / 2);
+        }
+        let (min, max) = balanced_range(24);
+        assert_eq!(Word::from_int_checked(max).unwrap().to_int(), max);
+        assert!(Word::from_int_checked(min - 1).is_err());
+    }
+
+    #[test]
+    fn test_all_values() {
+        assert_eq!(Tryte::all_values().count(), 3usize.pow(6));
+        let values: Vec<i16> = Tryte::all_values().map(Tryte::to_int).collect();
+        assert!(values.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        assert_eq!(values.first(), Some(&-364));
+        assert_eq!(values.last(), Some(&364));
+
+        let words: Vec<i64> = Word::all_values().take(3).map(Word::to_int).collect();
+        assert_eq!(words, [-141_214_768_240, -141_214_768_239, -141_214_768_238]);
+    }
+
+    #[test]
+    fn test_widening_mul() {
+        let base = 3i128.pow(24);
+        for (a, b) in [(7, -6), (141_214_768_240, 141_214_768_240), (-987_654_321, 123_456_789)] {
+            let (low, high) = from_i64(a).widening_mul(from_i64(b));
+            assert_eq!(low.to_int() as i128 + high.to_int() as i128 * base, a as i128 * b as i128);
+        }
+    }
+
+    #[test]
+    fn test_mul_add() {
+        let cases = [(0, 5, 7), (6, -7, 3), (-12_345, 678, -9), (376_000, 375_000, 1_000)];
+        for (x, a, b) in cases {
+            assert_eq!(from_i64(x).mul_add(from_i64(a), from_i64(b)).to_int(), x * a + b);
+        }
+        // 376_000^2 + 1 overflows a Word, the result is the exact value reduced into range.
+        let modulus = 3i128.pow(24);
+        let exact = 376_000i128 * 376_000 + 1;
+        let half = modulus / 2;
+        let wrapped = (exact + half).rem_euclid(modulus) - half;
+        let result = from_i64(376_000).mul_add(from_i64(376_000), from_i64(1));
+        assert_eq!(result.to_int() as i128, wrapped);
+    }
+
+    // The widest width i16 can back.
+    type Trits10 = BalancedInt<10>;
+    impl TernaryIntegerRepr for Trits10 {
+        type Int = i16;
+    }
+
+    // Misuse, which fails to compile once to_int or from_int is called:
+    //
+    //     type Trits12 = BalancedInt<12>;
+    //     impl TernaryIntegerRepr for Trits12 { type Int = i16; }
+    //     Trits12::from_int(1); // error: Int is too small to hold every value of this many trits
+
+    #[test]
+    fn test_int_sized_to_width() {
+        assert_eq!(Trits10::from_int(29524).to_int(), 29524);
+        assert_eq!(Trits10::from_int(-29524).to_int(), -29524);
+        assert_eq!(Trits10::from_int(-29524), Trits10::new([Trit::Neg; 10]));
+    }
+
+    #[test]
+    fn test_debug_assert_valid() {
+        from_i64(-141_214_768_240).debug_assert_valid();
+        Tryte::from_int(364).debug_assert_valid();
+        BalancedInt::<3>::try_from_i8_array([1, 0, -1]).unwrap().debug_assert_valid();
+    }
+
+    #[test]
+    fn test_abs_with_sign() {
+        assert_eq!(from_i64(42).abs_with_sign(), (from_i64(42), false));
+        assert_eq!(from_i64(-42).abs_with_sign(), (from_i64(42), true));
+        assert_eq!(Word::zero().abs_with_sign(), (Word::zero(), false));
+        let min = from_i64(-141_214_768_240);
+        assert_eq!(min.abs_with_sign(), (from_i64(141_214_768_240), true));
+    }
+
+    #[test]
+    fn test_negate_and_abs_assign() {
+        for value in [0, 1, -1, 42, -42, 141_214_768_240, -141_214_768_240] {
+            let word = from_i64(value);
+            let mut negated = word;
+            negated.negate_assign();
+            assert_eq!(negated, word.negate());
+            let mut abs = word;
+            abs.abs_assign();
+            assert_eq!(abs, word.abs());
+        }
+        let mut odd = BalancedInt::<7>::new([Trit::Pos, Trit::Zero, Trit::Neg, Trit::Zero, Trit::Zero, Trit::Zero, Trit::Neg]);
+        let expected = odd.abs();
+        odd.abs_assign();
+        assert_eq!(odd, expected);
+    }
+
+    #[test]
+    fn test_abs_and_neg_at_any_width() {
+        let value = Trits10::from_int(-29524);
+        assert_eq!(value.sign(), Trit::Neg);
+        assert_eq!(value.abs().to_int(), 29524);
+        assert_eq!((-value).to_int(), 29524);
+        assert_eq!(Trits10::from_int(42).abs().to_int(), 42);
+        assert!(Trits10::zero().abs().is_zero());
+
+        // Widths without an integer backing at all.
+        let odd = BalancedInt::<7>::new([Trit::Pos, Trit::Zero, Trit::Neg, Trit::Zero, Trit::Zero, Trit::Zero, Trit::Neg]);
+        assert_eq!(odd.abs(), -odd);
+        assert_eq!(odd.abs().sign(), Trit::Pos);
+    }
+
     #[test]
     fn test_display_format() {
         // String format should have no spaces or extra chars, just trits
         assert_eq!(from_i64(13).to_string(), "000000000000000000000111");
         assert_eq!(from_i64(-13).to_string(), "000000000000000000000TTT");
     }
+
+    #[test]
+    fn test_display_width() {
+        let tryte = Tryte::from_int(11);
+        assert_eq!(format!("{:8}", tryte), "     11T");
+        assert_eq!(format!("{:08}", tryte), "0000011T");
+        assert_eq!(format!("{:<8}|", tryte), "11T     |");
+        // Narrower than the value keeps every significant trit.
+        assert_eq!(format!("{:2}", tryte), "11T");
+        assert_eq!(format!("{:>12}", from_i64(-13)), "         TTT");
+        assert_eq!(format!("{:3}", Word::zero()), "  0");
+        // Without a width all N trits are printed as before.
+        assert_eq!(format!("{}", tryte), "00011T");
+    }
+
+    #[test]
+    fn test_display_sign_flag() {
+        assert_eq!(format!("{:+5}", BalancedInt::<6>::from_int(11)), " +11T");
+        assert_eq!(format!("{:+5}", BalancedInt::<6>::from_int(-11)), "  TT1");
+        assert_eq!(format!("{:+05}", BalancedInt::<6>::from_int(-11)), "00TT1");
+        assert_eq!(format!("{:<+5}|", BalancedInt::<6>::from_int(-11)), "TT1  |");
+        assert_eq!(format!("{:+}", BalancedInt::<6>::from_int(-11)), "TT1");
+        assert_eq!(format!("{:+}", BalancedInt::<6>::zero()), "+0");
+    }
 }
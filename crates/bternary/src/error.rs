@@ -0,0 +1,51 @@
+//! One error type covering every bternary failure, for callers that handle them alike.
+
+use std::num::TryFromIntError;
+use thiserror::Error;
+use crate::balanced_int::BIntError;
+use crate::tfixed::TFixedError;
+use crate::trit::InvalidTritValueError;
+use crate::tryte::TryteConversionError;
+
+/// Any error the crate returns, converting from each with `From` so `?` can mix them.
+/// The specific error is kept inside for callers that want to tell them apart.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    #[error(transparent)]
+    BInt(#[from] BIntError),
+    #[error(transparent)]
+    TryteConversion(#[from] TryteConversionError),
+    #[error(transparent)]
+    TryFromInt(#[from] TryFromIntError),
+    #[error(transparent)]
+    InvalidTrit(#[from] InvalidTritValueError),
+    #[error(transparent)]
+    TFixed(#[from] TFixedError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TFixed, Trit, Tryte, Word};
+
+    #[test]
+    fn test_sources_convert() {
+        let bint = || -> Result<Word, Error> { Ok(Word::from_int_checked(i64::MAX)?) };
+        assert!(matches!(bint(), Err(Error::BInt(BIntError::ValueDoesNotFit(..)))));
+        let tryte = || -> Result<Tryte, Error> { Ok(Tryte::try_from(365i16)?) };
+        assert_eq!(tryte(), Err(Error::TryteConversion(TryteConversionError(365))));
+        let int = || -> Result<i8, Error> { Ok(i8::try_from(300i32)?) };
+        assert!(matches!(int(), Err(Error::TryFromInt(_))));
+        let trit = || -> Result<Trit, Error> { Ok(Trit::try_from(2i8)?) };
+        assert_eq!(trit(), Err(Error::InvalidTrit(InvalidTritValueError(2))));
+        let fixed = || -> Result<TFixed, Error> { Ok(TFixed::from_f64(f64::NAN)?) };
+        assert_eq!(fixed(), Err(Error::TFixed(TFixedError::NotANumber)));
+    }
+
+    #[test]
+    fn test_display_is_the_source() {
+        let source = BIntError::RangeInvalid(3, 2);
+        assert_eq!(Error::from(source.clone()).to_string(), source.to_string());
+        assert_eq!(Error::from(InvalidTritValueError(5)).to_string(), "Invalid value for Trit: 5");
+    }
+}
@@ -1,10 +1,28 @@
+mod macros;
+pub mod error;
 pub mod trit;
 pub mod balanced_int;
 pub mod tryte;
+pub mod nibble;
 pub mod word;
+pub mod long_word;
+pub mod sequence;
+pub mod tfloat;
+pub mod tfixed;
+pub mod shift_register;
+#[cfg(feature = "packed-tryte")]
+pub mod packed;
+#[cfg(feature = "proptest")]
+pub mod strategy;
 
-use thiserror::Error;
-pub use trit::Trit;
-pub use tryte::Tryte;
+pub use balanced_int::{balanced_range, pow3};
+pub use error::Error;
+pub use trit::{Carry, Trit};
+pub use tryte::{Tryte, TryteConversionError};
+pub use nibble::Nibble;
 pub use word::Word;
+pub use long_word::LongWord;
+pub use tfloat::TFloat;
+pub use tfixed::{TFixed, TFixedError};
+pub use shift_register::ShiftRegister;
 
@@ -0,0 +1,47 @@
+//! A 40 trit integer, the widest `i64` can hold, backed by `i128` for headroom.
+
+use crate::balanced_int::{ArithmeticTernaryInteger, BalancedInt, TernaryIntegerRepr};
+
+pub const TRITS_IN_LONG_WORD: usize = 40;
+
+/// `±(3^40 - 1) / 2`, about `±6.1e18`.
+pub type LongWord = BalancedInt<TRITS_IN_LONG_WORD>;
+
+impl TernaryIntegerRepr for LongWord {
+    type Int = i128;
+}
+
+impl ArithmeticTernaryInteger for LongWord {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Trit;
+
+    const LONG_WORD_MAX: i128 = (3i128.pow(40) - 1) / 2;
+
+    #[test]
+    fn test_extremes_round_trip() {
+        assert_eq!(LongWord::MAX.to_int(), LONG_WORD_MAX);
+        assert_eq!(LongWord::MIN.to_int(), -LONG_WORD_MAX);
+        for value in [0, 1, -1, LONG_WORD_MAX, -LONG_WORD_MAX, LONG_WORD_MAX - 1, i64::MAX as i128 / 3] {
+            assert_eq!(LongWord::from_int(value).to_int(), value);
+        }
+        assert_eq!(LongWord::from_int(LONG_WORD_MAX), LongWord::new([Trit::Pos; 40]));
+        // Past the range it wraps like every other width.
+        assert_eq!(LongWord::from_int(LONG_WORD_MAX + 1), LongWord::MIN);
+        assert!(LongWord::from_int_checked(LONG_WORD_MAX + 1).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = LongWord::from_int(4_000_000_000_000_000_000);
+        let b = LongWord::from_int(-1_500_000_000_000_000_000);
+        assert_eq!((a + b).to_int(), 2_500_000_000_000_000_000);
+        assert_eq!((a - b).to_int(), 5_500_000_000_000_000_000);
+        // 8e18 is past the top and wraps by 3^40.
+        assert_eq!((a + a).to_int(), 8_000_000_000_000_000_000 - 3i128.pow(40));
+        let product = LongWord::from_int(3_000_000_000) * LongWord::from_int(-2_000_000_000);
+        assert_eq!(product.to_int(), -6_000_000_000_000_000_000);
+    }
+}
@@ -0,0 +1,54 @@
+/// A Tryte from a constant integer expression, e.g. `tryte!(-5)`.
+///
+/// The range `-364..=364` is checked at compile time, so an out of range value fails to build
+/// with "tryte! value out of range" instead of wrapping like `Tryte::from_int`.
+#[macro_export]
+macro_rules! tryte {
+    ($value:expr) => {{
+        const {
+            let value: i16 = $value;
+            let (min, max) = $crate::balanced_int::balanced_range(6);
+            assert!(min as i16 <= value && value <= max as i16, concat!("tryte! value out of range: ", stringify!($value)));
+        };
+        $crate::Tryte::from_int($value)
+    }};
+}
+
+/// A Word from a constant integer expression, e.g. `word!(12345)`.
+///
+/// The range `-141_214_768_240..=141_214_768_240` is checked at compile time, so an out of range
+/// value fails to build with "word! value out of range" instead of wrapping like `Word::from_int`.
+#[macro_export]
+macro_rules! word {
+    ($value:expr) => {{
+        const {
+            let value: i64 = $value;
+            let (min, max) = $crate::balanced_int::balanced_range(24);
+            assert!(
+                min <= value && value <= max,
+                concat!("word! value out of range: ", stringify!($value))
+            );
+        };
+        $crate::Word::from_int($value)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Tryte, Word};
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(tryte!(-5), Tryte::from_int(-5));
+        assert_eq!(tryte!(364).to_int(), 364);
+        assert_eq!(tryte!(-364).to_int(), -364);
+        assert_eq!(word!(12345).to_int(), 12345);
+        assert_eq!(word!(-141_214_768_240).to_int(), -141_214_768_240);
+        assert_eq!(word!(3 * 9), Word::from_int(27));
+    }
+
+    // Out of range literals fail to compile:
+    //
+    //     tryte!(365);                 // error: tryte! value out of range: 365
+    //     word!(141_214_768_241);      // error: word! value out of range: 141_214_768_241
+}
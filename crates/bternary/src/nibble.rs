@@ -0,0 +1,42 @@
+//! A 4 trit half tryte, for compact fields.
+
+use crate::balanced_int::{ArithmeticTernaryInteger, BalancedInt, TernaryIntegerRepr};
+
+pub const TRITS_IN_NIBBLE: usize = 4;
+
+/// `-40..=40`, enough for a register index.
+pub type Nibble = BalancedInt<TRITS_IN_NIBBLE>;
+
+impl TernaryIntegerRepr for Nibble {
+    type Int = i16;
+}
+
+impl ArithmeticTernaryInteger for Nibble {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        assert_eq!(Nibble::MAX.to_int(), 40);
+        assert_eq!(Nibble::MIN.to_int(), -40);
+        for value in -40..=40 {
+            assert_eq!(Nibble::from_int(value).to_int(), value);
+        }
+        assert!(Nibble::from_int_checked(41).is_err());
+        assert_eq!(Nibble::from_int(41), Nibble::MIN);
+        assert_eq!(Nibble::MAX.to_string(), "1111");
+        assert_eq!(Nibble::from_int(-2).to_string(), "00T1");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Nibble::from_int(13), Nibble::from_int(-5));
+        assert_eq!((a + b).to_int(), 8);
+        assert_eq!((a - b).to_int(), 18);
+        assert_eq!((a * b).to_int(), -65 + 81);
+        assert_eq!((a / b).to_int(), -3);
+        assert_eq!((Nibble::MAX + Nibble::from_int(1)), Nibble::MIN);
+    }
+}
@@ -0,0 +1,364 @@
+//! The 2 byte `Tryte`, enabled with the `packed-tryte` feature.
+//!
+//! Without the feature `Tryte` is `BalancedInt<6>`, one byte per trit. With it `Tryte` is this
+//! newtype over a `u16`, so large tryte buffers take a third of the memory. It dereferences to
+//! the equal `BalancedInt<6>`, which keeps every `&self` method working unchanged, and forwards
+//! the constructors and mutators. Trits can't be written through `IndexMut` or `iter_mut`, a
+//! packed trit has no byte of its own to borrow.
+
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+use crate::balanced_int::{BIntError, BalancedInt, ParseBalancedIntError};
+use crate::tryte::TRITS_IN_TRYTE;
+use crate::trit::InvalidTritValueError;
+use crate::Trit;
+
+/// Number of tryte values, `3^6`.
+const TRYTE_VALUES: usize = 729;
+
+/// Every tryte indexed by its trit codes read as a base 3 number, which is `value + 364`.
+/// `Deref` hands out references into this table.
+static UNPACKED: [BalancedInt<TRITS_IN_TRYTE>; TRYTE_VALUES] = unpacked_table();
+
+const fn unpacked_table() -> [BalancedInt<TRITS_IN_TRYTE>; TRYTE_VALUES] {
+    let mut table = [BalancedInt::new([Trit::Zero; TRITS_IN_TRYTE]); TRYTE_VALUES];
+    let mut index = 0;
+    while index < TRYTE_VALUES {
+        let mut trits = [Trit::Zero; TRITS_IN_TRYTE];
+        let mut digits = index;
+        let mut i = 0;
+        while i < TRITS_IN_TRYTE {
+            trits[i] = match digits % 3 {
+                0 => Trit::Neg,
+                1 => Trit::Zero,
+                _ => Trit::Pos,
+            };
+            digits /= 3;
+            i += 1;
+        }
+        table[index] = BalancedInt::new(trits);
+        index += 1;
+    }
+    table
+}
+
+/// A tryte stored in a `u16`, 2 bits per trit with trit `i` in bits `2i..2i+2`.
+/// Each trit is stored as `value + 1`, so the all zero tryte is `0b01_01_01_01_01_01`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Tryte(u16);
+
+impl Tryte {
+    fn pack(value: BalancedInt<TRITS_IN_TRYTE>) -> Self {
+        let bits = value.iter().enumerate()
+            .fold(0u16, |bits, (i, &t)| bits | (((t as i8 + 1) as u16) << (2 * i)));
+        Tryte(bits)
+    }
+
+    /// Position in `UNPACKED`.
+    fn index(self) -> usize {
+        (0..TRITS_IN_TRYTE).rev()
+            .fold(0, |acc, i| acc * 3 + ((self.0 >> (2 * i)) & 0b11) as usize)
+    }
+
+    fn unpack(self) -> BalancedInt<TRITS_IN_TRYTE> {
+        let value = *self;
+        #[cfg(debug_assertions)]
+        value.debug_assert_valid();
+        value
+    }
+
+    /// Checks in debug builds that every 2 bit code is a trit and nothing is set above them.
+    /// The packed counterpart of `BalancedInt::debug_assert_valid`.
+    pub fn debug_assert_valid(&self) {
+        debug_assert!(
+            self.0 >> (2 * TRITS_IN_TRYTE) == 0
+                && (0..TRITS_IN_TRYTE).all(|i| (self.0 >> (2 * i)) & 0b11 != 0b11),
+            "invalid packed tryte {:#014b}",
+            self.0
+        );
+    }
+
+    /// Runs `f` on the unpacked value and packs the result back.
+    fn modify<R>(&mut self, f: impl FnOnce(&mut BalancedInt<TRITS_IN_TRYTE>) -> R) -> R {
+        let mut value = self.unpack();
+        let result = f(&mut value);
+        *self = Tryte::pack(value);
+        self.debug_assert_valid();
+        result
+    }
+
+    /// 364, every code `Pos`.
+    pub const MAX: Self = Tryte(0b10_10_10_10_10_10);
+    /// -364, every code `Neg`.
+    pub const MIN: Self = Tryte(0);
+
+    pub fn max_value() -> i16 {
+        BalancedInt::<TRITS_IN_TRYTE>::max_value()
+    }
+
+    pub fn min_value() -> i16 {
+        BalancedInt::<TRITS_IN_TRYTE>::min_value()
+    }
+
+    pub fn zero() -> Self {
+        Tryte::pack(BalancedInt::zero())
+    }
+
+    pub fn new(trits: [Trit; TRITS_IN_TRYTE]) -> Self {
+        Tryte::pack(BalancedInt::new(trits))
+    }
+
+    pub fn from_trits_msb_first(trits: [Trit; TRITS_IN_TRYTE]) -> Self {
+        Tryte::pack(BalancedInt::from_trits_msb_first(trits))
+    }
+
+    pub fn try_from_i8_array(values: [i8; TRITS_IN_TRYTE]) -> Result<Self, InvalidTritValueError> {
+        BalancedInt::try_from_i8_array(values).map(Tryte::pack)
+    }
+
+    pub fn from_rle(runs: &[(Trit, u16)]) -> Result<Self, BIntError> {
+        BalancedInt::from_rle(runs).map(Tryte::pack)
+    }
+
+    pub fn from_setun_str(s: &str) -> Result<Self, ParseBalancedIntError> {
+        BalancedInt::from_setun_str(s).map(Tryte::pack)
+    }
+
+    pub fn from_int(value: i16) -> Self {
+        Tryte::pack(BalancedInt::from_int(value))
+    }
+
+    pub fn from_int_checked(value: i16) -> Result<Self, BIntError> {
+        BalancedInt::from_int_checked(value).map(Tryte::pack)
+    }
+
+    /// Every tryte from -364 to 364, read straight from the unpacked table.
+    pub fn all_values() -> impl Iterator<Item = Self> {
+        UNPACKED.iter().map(|&value| Tryte::pack(value))
+    }
+
+    pub fn to_int(self) -> i16 {
+        self.unpack().to_int()
+    }
+
+    pub fn write_trit_range<T>(&mut self, value: T, start: usize, end: usize) -> Result<(), BIntError>
+    where
+        T: Copy + TryInto<i64>,
+    {
+        self.modify(|unpacked| unpacked.write_trit_range(value, start, end))
+    }
+
+    /// Negates without unpacking. Each 2 bit code `c` becomes `2 - c`, and no field can borrow
+    /// from the next, so that is one subtraction from all codes 2.
+    pub fn negate_assign(&mut self) {
+        self.0 = 0b10_10_10_10_10_10 - self.0;
+        self.debug_assert_valid();
+    }
+
+    pub fn abs_assign(&mut self) {
+        if self.sign() == Trit::Neg {
+            self.negate_assign();
+        }
+        self.debug_assert_valid();
+    }
+}
+
+impl Deref for Tryte {
+    type Target = BalancedInt<TRITS_IN_TRYTE>;
+
+    fn deref(&self) -> &Self::Target {
+        #[cfg(debug_assertions)]
+        self.debug_assert_valid();
+        &UNPACKED[self.index()]
+    }
+}
+
+impl Default for Tryte {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<BalancedInt<TRITS_IN_TRYTE>> for Tryte {
+    fn from(value: BalancedInt<TRITS_IN_TRYTE>) -> Self {
+        Tryte::pack(value)
+    }
+}
+
+impl From<Tryte> for BalancedInt<TRITS_IN_TRYTE> {
+    fn from(tryte: Tryte) -> Self {
+        tryte.unpack()
+    }
+}
+
+impl From<[Trit; TRITS_IN_TRYTE]> for Tryte {
+    fn from(trits: [Trit; TRITS_IN_TRYTE]) -> Self {
+        Tryte::new(trits)
+    }
+}
+
+impl From<Trit> for Tryte {
+    fn from(trit: Trit) -> Self {
+        Tryte::pack(BalancedInt::from(trit))
+    }
+}
+
+impl TryFrom<i64> for Tryte {
+    type Error = BIntError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        BalancedInt::try_from(value).map(Tryte::pack)
+    }
+}
+
+impl std::str::FromStr for Tryte {
+    type Err = ParseBalancedIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Tryte::pack)
+    }
+}
+
+impl std::ops::Index<usize> for Tryte {
+    type Output = Trit;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &(**self)[index]
+    }
+}
+
+impl PartialEq<BalancedInt<TRITS_IN_TRYTE>> for Tryte {
+    fn eq(&self, other: &BalancedInt<TRITS_IN_TRYTE>) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<Tryte> for BalancedInt<TRITS_IN_TRYTE> {
+    fn eq(&self, other: &Tryte) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialOrd for Tryte {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tryte {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl std::ops::Neg for Tryte {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        self.negate_assign();
+        self
+    }
+}
+
+/// Formats like the unpacked `BalancedInt<6>`, so output doesn't depend on the feature.
+impl Debug for Tryte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl Display for Tryte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_unpacked_exhaustively() {
+        for value in -364..=364 {
+            let unpacked = BalancedInt::<TRITS_IN_TRYTE>::from_int(value);
+            let tryte = Tryte::from_int(value);
+            assert_eq!(tryte.to_int(), value);
+            assert_eq!(*tryte, unpacked);
+            assert_eq!(Tryte::from(unpacked), tryte);
+            assert_eq!(Tryte::new(std::array::from_fn(|i| unpacked[i])), tryte);
+            assert_eq!(tryte.to_string(), unpacked.to_string());
+            assert_eq!(format!("{:?}", tryte), format!("{:?}", unpacked));
+            assert_eq!(tryte.to_string().parse(), Ok(tryte));
+            assert_eq!(Tryte::try_from(value as i64), Ok(tryte));
+            for i in 0..TRITS_IN_TRYTE {
+                assert_eq!(tryte[i], unpacked[i]);
+            }
+            assert!(tryte.iter().eq(unpacked.iter()));
+        }
+    }
+
+    #[test]
+    fn test_negate_and_abs_assign() {
+        for value in -364..=364 {
+            let unpacked = BalancedInt::<TRITS_IN_TRYTE>::from_int(value);
+            let mut negated = Tryte::from_int(value);
+            negated.negate_assign();
+            assert_eq!(negated, unpacked.negate());
+            assert_eq!(-Tryte::from_int(value), negated);
+            let mut abs = Tryte::from_int(value);
+            abs.abs_assign();
+            assert_eq!(abs, unpacked.abs());
+        }
+    }
+
+    #[test]
+    fn test_all_values_matches_unpacked() {
+        assert!(Tryte::all_values().eq(BalancedInt::<TRITS_IN_TRYTE>::all_values()));
+    }
+
+    #[test]
+    fn test_bounds_match_unpacked() {
+        assert_eq!(Tryte::MAX, BalancedInt::<TRITS_IN_TRYTE>::MAX);
+        assert_eq!(Tryte::MIN, BalancedInt::<TRITS_IN_TRYTE>::MIN);
+        assert_eq!((Tryte::min_value(), Tryte::max_value()), (-364, 364));
+    }
+
+    #[test]
+    fn test_ordering_matches_unpacked() {
+        for a in (-364..=364).step_by(13) {
+            for b in (-364..=364).step_by(17) {
+                let (ta, tb) = (Tryte::from_int(a), Tryte::from_int(b));
+                assert_eq!(ta.cmp(&tb), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_trit_range() {
+        let mut tryte = Tryte::zero();
+        tryte.write_trit_range(-4, 2, 4).unwrap();
+        assert_eq!(tryte.to_int(), -36);
+        assert_eq!(tryte.write_trit_range(100, 0, 2), Err(BIntError::ValueDoesNotFit(0, 2)));
+        assert_eq!(tryte.to_int(), -36);
+    }
+
+    #[test]
+    fn test_memory_use() {
+        assert_eq!(size_of::<Tryte>(), 2);
+        assert_eq!(size_of::<BalancedInt<TRITS_IN_TRYTE>>(), 6);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid packed tryte")]
+    fn test_invalid_code_caught_in_debug() {
+        let _ = Tryte(0b01_01_11).to_int();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_range() {
+        let _ = Tryte::zero()[6];
+    }
+}
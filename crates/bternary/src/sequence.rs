@@ -0,0 +1,57 @@
+use crate::Word;
+
+/// Builds `n` Words starting from `seed`, each produced by applying `step` to the previous one.
+pub fn iterate<F>(seed: Word, mut step: F, n: usize) -> Vec<Word>
+where
+    F: FnMut(Word) -> Word,
+{
+    let mut words = Vec::with_capacity(n);
+    let mut current = seed;
+    for _ in 0..n {
+        words.push(current);
+        current = step(current);
+    }
+    words
+}
+
+/// The first `n` Fibonacci numbers starting at F(0) = 0, so `fib_words(n)[i]` is F(i).
+/// Values wrap like `Add` once they pass the Word range, which happens after F(55).
+pub fn fib_words(n: usize) -> Vec<Word> {
+    // `next` runs one step ahead of the value handed to the closure.
+    let mut next = Word::from_int(1);
+    iterate(Word::zero(), |current| {
+        let following = current + next;
+        std::mem::replace(&mut next, following)
+    }, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iterate() {
+        let powers = iterate(Word::from_int(1), |w| w + w + w, 5);
+        let values: Vec<i64> = powers.iter().map(|w| w.to_int()).collect();
+        assert_eq!(values, vec![1, 3, 9, 27, 81]);
+        assert!(iterate(Word::zero(), |w| w, 0).is_empty());
+    }
+
+    #[test]
+    fn test_fib_words() {
+        let values: Vec<i64> = fib_words(10).iter().map(|w| w.to_int()).collect();
+        assert_eq!(values, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(fib_words(21)[20].to_int(), 6765);
+    }
+
+    #[test]
+    fn test_fib_words_long_chain() {
+        // F(55) is the largest Fibonacci number that fits in a Word.
+        let mut a = 0i64;
+        let mut b = 1i64;
+        for (i, word) in fib_words(56).iter().enumerate() {
+            assert_eq!(word.to_int(), a, "F({})", i);
+            (a, b) = (b, a + b);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! A serial in, parallel out ternary shift register, for modelling hardware rather than arithmetic.
+
+use crate::balanced_int::BalancedInt;
+use crate::Trit;
+
+/// `N` trit cells clocked one trit at a time.
+///
+/// Trits enter at cell 0 and move toward cell `N - 1`, one cell per `shift_in`, dropping out
+/// of the top after `N` shifts. Numerically that is `shift_left` with the new trit as the least
+/// significant one, except the top trit comes back out instead of being lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShiftRegister<const N: usize>(BalancedInt<N>);
+
+impl<const N: usize> ShiftRegister<N> {
+    /// A register with every cell zero.
+    pub fn new() -> Self {
+        ShiftRegister(BalancedInt::zero())
+    }
+
+    /// Clocks `trit` into cell 0, moving every cell up one, and returns the trit that leaves
+    /// cell `N - 1`. With no cells the input comes straight back out.
+    pub fn shift_in(&mut self, trit: Trit) -> Trit {
+        if N == 0 {
+            return trit;
+        }
+        let out = self.0[N - 1];
+        for i in (1..N).rev() {
+            self.0[i] = self.0[i - 1];
+        }
+        self.0[0] = trit;
+        out
+    }
+
+    /// Overwrites every cell at once.
+    pub fn parallel_load(&mut self, value: impl Into<BalancedInt<N>>) {
+        self.0 = value.into();
+    }
+
+    /// The cells as a value, cell 0 being the least significant trit.
+    pub fn contents(&self) -> BalancedInt<N> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tryte;
+
+    #[test]
+    fn test_shift_sequence_through() {
+        let input = [Trit::Pos, Trit::Neg, Trit::Zero, Trit::Pos, Trit::Pos];
+        let mut register = ShiftRegister::<3>::new();
+        let mut outputs = Vec::new();
+        // Three zeros flush the input back out after it.
+        for trit in input.into_iter().chain([Trit::Zero; 3]) {
+            outputs.push(register.shift_in(trit));
+        }
+        assert_eq!(outputs[..3], [Trit::Zero; 3]);
+        assert_eq!(outputs[3..], input);
+        assert_eq!(register.contents(), BalancedInt::zero());
+    }
+
+    #[test]
+    fn test_parallel_load() {
+        let mut register = ShiftRegister::<6>::new();
+        register.parallel_load(Tryte::from_int(-364));
+        assert_eq!(register.shift_in(Trit::Pos), Trit::Neg);
+        // The top T left and the new 1 came in at the bottom, TTTTT1 = 1 - 363.
+        assert_eq!(register.contents(), "TTTTT1".parse::<BalancedInt<6>>().unwrap());
+        assert_eq!(register.contents().to_int(), -362);
+        let mut empty = ShiftRegister::<0>::new();
+        assert_eq!(empty.shift_in(Trit::Neg), Trit::Neg);
+    }
+}
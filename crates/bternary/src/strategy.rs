@@ -0,0 +1,55 @@
+//! Proptest strategies for generating balanced integers, enabled by the `proptest` feature.
+
+use proptest::prelude::*;
+use crate::balanced_int::BalancedInt;
+use crate::{Trit, Word};
+
+/// Any trit with equal probability.
+pub fn arb_trit() -> impl Strategy<Value = Trit> {
+    prop_oneof![Just(Trit::Neg), Just(Trit::Zero), Just(Trit::Pos)]
+}
+
+/// Any `BalancedInt<N>`.
+///
+/// Every value has exactly one trit array, so uniform trits are already uniform over the value
+/// range. That rarely hits the edges though, so a share of cases are small magnitudes (only the
+/// low trits set) or the extremes `±(3^N - 1) / 2`.
+pub fn arb_balanced_int<const N: usize>() -> impl Strategy<Value = BalancedInt<N>> {
+    let low_trits = N.min(3);
+    prop_oneof![
+        6 => proptest::array::uniform(arb_trit()).prop_map(BalancedInt::new),
+        2 => proptest::collection::vec(arb_trit(), low_trits).prop_map(|low| {
+            let mut value = BalancedInt::<N>::zero();
+            for (i, trit) in low.into_iter().enumerate() {
+                value[i] = trit;
+            }
+            value
+        }),
+        1 => prop_oneof![
+            Just(BalancedInt::new([Trit::Pos; N])),
+            Just(BalancedInt::new([Trit::Neg; N])),
+        ],
+    ]
+}
+
+/// Any Word, see `arb_balanced_int`.
+pub fn arb_word() -> impl Strategy<Value = Word> {
+    arb_balanced_int()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_int_round_trip(word in arb_word()) {
+            prop_assert_eq!(Word::from_int(word.to_int()), word);
+        }
+
+        #[test]
+        fn test_value_in_range(word in arb_word()) {
+            prop_assert!(word.to_int().abs() <= 141_214_768_240);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! A ternary fixed point number, a Word counting in steps of `3^-12`.
+
+use std::ops::{Add, Neg, Sub};
+use thiserror::Error;
+use crate::balanced_int::{balanced_range, pow3};
+use crate::Word;
+
+/// Trits after the ternary point.
+pub const FRAC_TRITS: u32 = 12;
+/// `3^FRAC_TRITS`, the raw value of `1.0`.
+const SCALE: i64 = pow3(FRAC_TRITS);
+const RAW_MIN: i64 = balanced_range(24).0;
+const RAW_MAX: i64 = balanced_range(24).1;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum TFixedError {
+    #[error("{0} is outside the fixed point range")]
+    OutOfRange(f64),
+    #[error("NaN has no fixed point value")]
+    NotANumber,
+}
+
+/// `raw / 3^12`: 12 integer trits and 12 fractional trits, so values step by `3^-12` between
+/// `MIN` and `MAX`, about `±265720.5`. Arithmetic wraps like the Word underneath.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct TFixed(Word);
+
+impl TFixed {
+    pub const ZERO: TFixed = TFixed(Word::const_from_i64(0));
+    pub const ONE: TFixed = TFixed(Word::const_from_i64(SCALE));
+    /// `(3^24 - 1) / 2 / 3^12`, every trit `Pos`.
+    pub const MAX: TFixed = TFixed(Word::const_from_i64(RAW_MAX));
+    /// `-MAX`, every trit `Neg`.
+    pub const MIN: TFixed = TFixed(Word::const_from_i64(RAW_MIN));
+
+    pub fn from_raw(raw: Word) -> Self {
+        TFixed(raw)
+    }
+
+    pub fn raw(&self) -> Word {
+        self.0
+    }
+
+    /// Nearest TFixed to `value`, erroring if it rounds to outside `MIN..=MAX` or is NaN.
+    pub fn from_f64(value: f64) -> Result<Self, TFixedError> {
+        if value.is_nan() {
+            return Err(TFixedError::NotANumber);
+        }
+        let raw = (value * SCALE as f64).round();
+        if raw < RAW_MIN as f64 || raw > RAW_MAX as f64 {
+            return Err(TFixedError::OutOfRange(value));
+        }
+        Ok(TFixed(Word::from_int(raw as i64)))
+    }
+
+    /// Nearest TFixed to `value`, clamping anything below `MIN` (about -265720.5) to `MIN` and
+    /// anything above `MAX` (about 265720.5) to `MAX`, infinities included. NaN becomes zero.
+    pub fn from_f64_saturating(value: f64) -> Self {
+        if value.is_nan() {
+            return TFixed::ZERO;
+        }
+        let raw = (value * SCALE as f64).round().clamp(RAW_MIN as f64, RAW_MAX as f64);
+        TFixed(Word::from_int(raw as i64))
+    }
+
+    /// True when `|self - other| <= |epsilon|`. The difference is taken exactly, it can't wrap.
+    pub fn approx_eq(&self, other: &Self, epsilon: Self) -> bool {
+        (self.0.to_int() - other.0.to_int()).abs() <= epsilon.0.to_int().abs()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_int() as f64 / SCALE as f64
+    }
+}
+
+impl Add for TFixed {
+    type Output = TFixed;
+
+    fn add(self, rhs: TFixed) -> TFixed {
+        TFixed(self.0 + rhs.0)
+    }
+}
+
+impl Sub for TFixed {
+    type Output = TFixed;
+
+    fn sub(self, rhs: TFixed) -> TFixed {
+        TFixed(self.0 - rhs.0)
+    }
+}
+
+impl Neg for TFixed {
+    type Output = TFixed;
+
+    fn neg(self) -> TFixed {
+        TFixed(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(TFixed::from_f64(1.0), Ok(TFixed::ONE));
+        assert_eq!(TFixed::from_f64(-2.0).unwrap().to_f64(), -2.0);
+        assert_eq!(TFixed::from_f64(1.0 / 3.0).unwrap().raw().to_int(), SCALE / 3);
+        assert_eq!(TFixed::from_f64(1e6), Err(TFixedError::OutOfRange(1e6)));
+        assert_eq!(TFixed::from_f64(f64::NEG_INFINITY), Err(TFixedError::OutOfRange(f64::NEG_INFINITY)));
+        assert_eq!(TFixed::from_f64(f64::NAN), Err(TFixedError::NotANumber));
+        assert_eq!(TFixed::from_f64(TFixed::MAX.to_f64()), Ok(TFixed::MAX));
+    }
+
+    #[test]
+    fn test_from_f64_saturating() {
+        assert_eq!(TFixed::from_f64_saturating(1e6), TFixed::MAX);
+        assert_eq!(TFixed::from_f64_saturating(-1e6), TFixed::MIN);
+        assert_eq!(TFixed::from_f64_saturating(f64::INFINITY), TFixed::MAX);
+        assert_eq!(TFixed::from_f64_saturating(f64::MIN), TFixed::MIN);
+        assert_eq!(TFixed::from_f64_saturating(f64::NAN), TFixed::ZERO);
+        // In range values match the checked conversion.
+        assert_eq!(Ok(TFixed::from_f64_saturating(-12.25)), TFixed::from_f64(-12.25));
+        assert!((TFixed::MAX.to_f64() - 265_720.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = TFixed::from_f64(2.0).unwrap();
+        let b = TFixed::from_f64(2.05).unwrap();
+        assert!(a.approx_eq(&b, TFixed::from_f64(0.1).unwrap()));
+        assert!(b.approx_eq(&a, TFixed::from_f64(-0.1).unwrap()));
+        assert!(!a.approx_eq(&b, TFixed::from_f64(0.01).unwrap()));
+        assert!(a.approx_eq(&a, TFixed::ZERO));
+        assert!(!TFixed::MAX.approx_eq(&TFixed::MIN, TFixed::MAX));
+        assert!(TFixed::MAX.approx_eq(&TFixed::ZERO, TFixed::MAX));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = TFixed::from_f64(1.5).unwrap();
+        let b = TFixed::from_f64(0.25).unwrap();
+        assert!(((a + b).to_f64() - 1.75).abs() < 1e-5);
+        assert!(((a - b).to_f64() - 1.25).abs() < 1e-5);
+        assert_eq!(-TFixed::MAX, TFixed::MIN);
+    }
+}
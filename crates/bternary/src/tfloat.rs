@@ -0,0 +1,271 @@
+//! A saturating ternary floating point number with no NaN or infinities.
+
+use std::ops::{Add, Mul, Neg};
+use crate::balanced_int::balanced_range;
+use crate::{Carry, Trit, Word};
+
+/// Mantissa width, the trits of a Word.
+const MANTISSA_TRITS: usize = 24;
+/// Largest exponent magnitude, the tryte range.
+const EXPONENT_MAX: i32 = balanced_range(6).1 as i32;
+
+/// `mantissa * 3^exponent`, with a Word mantissa and a tryte sized exponent in `-364..=364`.
+///
+/// The exponent is balanced like every other value here, so it is stored as is with no bias.
+///
+/// Values are normalized so the mantissa's highest trit (trit 23) is nonzero, which makes the
+/// representation unique: `1.0` is `3^23 * 3^-23`. Two exceptions:
+/// - zero is a zero mantissa with exponent 0.
+/// - values too small to normalize at exponent -364 keep a smaller mantissa, and round to zero
+///   once nothing is left.
+///
+/// Nothing is ever NaN or infinite. Results too large saturate to `MAX` or `MIN`, and every
+/// rounding is to nearest, which is what dropping balanced trits does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TFloat {
+    mantissa: Word,
+    exponent: i16,
+}
+
+impl TFloat {
+    pub const ZERO: TFloat = TFloat { mantissa: Word::const_from_i64(0), exponent: 0 };
+    /// Every mantissa trit `Pos` at the largest exponent.
+    pub const MAX: TFloat = TFloat {
+        mantissa: Word::const_from_i64(balanced_range(MANTISSA_TRITS as u32).1),
+        exponent: EXPONENT_MAX as i16,
+    };
+    pub const MIN: TFloat = TFloat {
+        mantissa: Word::const_from_i64(balanced_range(MANTISSA_TRITS as u32).0),
+        exponent: EXPONENT_MAX as i16,
+    };
+
+    /// `mantissa * 3^exponent`, normalized and saturated.
+    pub fn new(mantissa: Word, exponent: i16) -> Self {
+        TFloat::normalize(mantissa, exponent as i32)
+    }
+
+    pub fn mantissa(&self) -> Word {
+        self.mantissa
+    }
+
+    pub fn exponent(&self) -> i16 {
+        self.exponent
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    /// Nearest TFloat to `value`. Infinities and out of range values saturate, NaN becomes zero.
+    pub fn from_f64(value: f64) -> Self {
+        if value.is_nan() || value == 0.0 {
+            return TFloat::ZERO;
+        }
+        if value.is_infinite() {
+            return if value > 0.0 { TFloat::MAX } else { TFloat::MIN };
+        }
+
+        let max = TFloat::MAX.mantissa.to_int() as f64;
+        // Scale so the mantissa lands in 3^23..3^24, then down a trit if that overflows a Word.
+        let mut exponent = value.abs().log(3.0).floor() as i32 - (MANTISSA_TRITS as i32 - 1);
+        let mut mantissa = (value / 3f64.powi(exponent)).round();
+        if mantissa.abs() > max {
+            exponent += 1;
+            mantissa = (value / 3f64.powi(exponent)).round();
+        }
+        TFloat::normalize(Word::from_int(mantissa as i64), exponent)
+    }
+
+    pub fn abs(&self) -> Self {
+        TFloat { mantissa: self.mantissa.abs(), exponent: self.exponent }
+    }
+
+    /// `|self - other|`, rounded and saturated like any other sum.
+    pub fn abs_diff(&self, other: &Self) -> Self {
+        (*self + -*other).abs()
+    }
+
+    /// True when `abs_diff(other) <= epsilon.abs()`.
+    pub fn approx_eq(&self, other: &Self, epsilon: Self) -> bool {
+        let (diff, epsilon) = (self.abs_diff(other), epsilon.abs());
+        // Nonzero normalized values order by exponent first, values below the normal range
+        // share the smallest exponent and order by mantissa.
+        diff.is_zero()
+            || (!epsilon.is_zero()
+                && (diff.exponent, diff.mantissa) <= (epsilon.exponent, epsilon.mantissa))
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let mantissa = self.mantissa.to_int() as f64;
+        // Dividing by a positive power rounds once, where `powi` of a negative exponent doesn't.
+        let exponent = self.exponent as i32;
+        if exponent >= 0 {
+            mantissa * 3f64.powi(exponent)
+        } else {
+            mantissa / 3f64.powi(-exponent)
+        }
+    }
+
+    /// Shifts the highest nonzero trit of `mantissa` up to trit 23, then brings the exponent into
+    /// range by saturating or by denormalizing toward zero.
+    fn normalize(mut mantissa: Word, mut exponent: i32) -> Self {
+        let Some(msb) = mantissa.most_significant_trit() else {
+            return TFloat::ZERO;
+        };
+        let shift = MANTISSA_TRITS - 1 - msb;
+        mantissa = mantissa.shift_left(shift);
+        exponent -= shift as i32;
+
+        if exponent > EXPONENT_MAX {
+            return if mantissa.sign() == Trit::Pos { TFloat::MAX } else { TFloat::MIN };
+        }
+        if exponent < -EXPONENT_MAX {
+            let excess = (-EXPONENT_MAX - exponent) as usize;
+            mantissa = mantissa.shift_right(excess);
+            if mantissa.is_zero() {
+                return TFloat::ZERO;
+            }
+            exponent = -EXPONENT_MAX;
+        }
+        TFloat { mantissa, exponent: exponent as i16 }
+    }
+}
+
+impl Default for TFloat {
+    fn default() -> Self {
+        TFloat::ZERO
+    }
+}
+
+impl Neg for TFloat {
+    type Output = TFloat;
+
+    fn neg(self) -> TFloat {
+        TFloat { mantissa: -self.mantissa, exponent: self.exponent }
+    }
+}
+
+impl Add for TFloat {
+    type Output = TFloat;
+
+    /// Aligns the smaller operand to the larger exponent, rounding away its low trits.
+    fn add(self, rhs: TFloat) -> TFloat {
+        let (big, small) = if self.exponent >= rhs.exponent { (self, rhs) } else { (rhs, self) };
+        let aligned = small.mantissa.shift_right((big.exponent - small.exponent) as usize);
+        let (sum, carry) = big.mantissa.full_add(&aligned, Carry::ZERO);
+
+        if carry.is_zero() {
+            return TFloat::normalize(sum, big.exponent as i32);
+        }
+        // The carry becomes the new top trit, one trit up.
+        let mut mantissa = sum.shift_right(1);
+        mantissa[MANTISSA_TRITS - 1] = carry.into_trit();
+        TFloat::normalize(mantissa, big.exponent as i32 + 1)
+    }
+}
+
+impl Mul for TFloat {
+    type Output = TFloat;
+
+    /// Keeps the top 24 trits of the 48 trit mantissa product.
+    fn mul(self, rhs: TFloat) -> TFloat {
+        let (low, high) = self.mantissa.widening_mul(rhs.mantissa);
+        let exponent = self.exponent as i32 + rhs.exponent as i32;
+        let Some(high_msb) = high.most_significant_trit() else {
+            return TFloat::normalize(low, exponent);
+        };
+
+        // Take the 24 trits ending at the product's highest nonzero trit.
+        let drop = high_msb + 1;
+        let mut mantissa = high.shift_left(MANTISSA_TRITS - drop);
+        for i in 0..MANTISSA_TRITS - drop {
+            mantissa[i] = low[i + drop];
+        }
+        TFloat::normalize(mantissa, exponent + drop as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= b.abs() * 1e-10
+    }
+
+    #[test]
+    fn test_powers_of_three_are_exact() {
+        for k in -30..=30 {
+            let value = 3f64.powi(k);
+            let float = TFloat::from_f64(value);
+            // Negative powers of three aren't exact in f64 itself.
+            if k >= 0 {
+                assert_eq!(float.to_f64(), value, "3^{k}");
+            } else {
+                assert!(close(float.to_f64(), value), "3^{k}");
+            }
+            assert_eq!(float.mantissa().to_int(), 3i64.pow(23));
+            assert_eq!(float.exponent() as i32, k - 23);
+        }
+        assert_eq!(TFloat::from_f64(-27.0).to_f64(), -27.0);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = TFloat::from_f64(1.0);
+        let b = TFloat::from_f64(1.001);
+        assert!(a.approx_eq(&b, TFloat::from_f64(0.01)));
+        assert!(b.approx_eq(&a, TFloat::from_f64(-0.01)));
+        assert!(!a.approx_eq(&b, TFloat::from_f64(0.0001)));
+        assert!(a.approx_eq(&a, TFloat::ZERO));
+        assert!(!a.approx_eq(&b, TFloat::ZERO));
+        assert!(TFloat::from_f64(-500.0).approx_eq(&TFloat::from_f64(-499.5), TFloat::from_f64(0.5)));
+        assert!(!TFloat::from_f64(-500.0).approx_eq(&TFloat::from_f64(500.0), TFloat::from_f64(999.0)));
+        assert_eq!(b.abs_diff(&a), a.abs_diff(&b));
+    }
+
+    #[test]
+    fn test_normalized() {
+        let float = TFloat::new(Word::from_int(1), 5);
+        assert_eq!(float, TFloat::from_f64(243.0));
+        assert_ne!(float.mantissa()[23], Trit::Zero);
+        assert_eq!(TFloat::new(Word::zero(), 7), TFloat::ZERO);
+    }
+
+    #[test]
+    fn test_add() {
+        let sum = TFloat::from_f64(40.0) + TFloat::from_f64(2.0);
+        assert_eq!(sum.to_f64(), 42.0);
+        let sum = TFloat::from_f64(1.5) + TFloat::from_f64(-0.25);
+        assert!(close(sum.to_f64(), 1.25));
+        // The carry out of the mantissa moves the result up a trit, rounding 3^24 - 1 to 3^24.
+        let max = TFloat::new(Word::from_int(141_214_768_240), 0);
+        assert_eq!((max + max).to_f64(), 3f64.powi(24));
+        assert_eq!((TFloat::from_f64(9.0) + -TFloat::from_f64(9.0)), TFloat::ZERO);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!((TFloat::from_f64(6.0) * TFloat::from_f64(-7.0)).to_f64(), -42.0);
+        assert_eq!((TFloat::from_f64(9.0) * TFloat::from_f64(1.0 / 27.0)).to_f64(), 1.0 / 3.0);
+        let product = TFloat::from_f64(1.5) * TFloat::from_f64(4.1);
+        assert!(close(product.to_f64(), 6.15));
+        let product = TFloat::from_f64(-1e20) * TFloat::from_f64(3.7e-5);
+        assert!(close(product.to_f64(), -3.7e15));
+    }
+
+    #[test]
+    fn test_saturation() {
+        assert_eq!(TFloat::MAX * TFloat::from_f64(2.0), TFloat::MAX);
+        assert_eq!(TFloat::MAX * TFloat::from_f64(-2.0), TFloat::MIN);
+        assert_eq!(TFloat::MAX + TFloat::MAX, TFloat::MAX);
+        assert_eq!(TFloat::from_f64(f64::INFINITY), TFloat::MAX);
+        assert_eq!(TFloat::from_f64(f64::NEG_INFINITY), TFloat::MIN);
+        assert_eq!(TFloat::from_f64(f64::NAN), TFloat::ZERO);
+        assert_eq!(TFloat::from_f64(1e300), TFloat::MAX);
+
+        let tiny = TFloat::from_f64(1e-150);
+        assert_eq!(tiny * tiny, TFloat::ZERO);
+        assert_eq!(TFloat::from_f64(1e-300), TFloat::ZERO);
+    }
+}
@@ -38,6 +38,89 @@ impl Trit {
             Trit::Pos => Trit::Neg,
         }
     }
+
+    /// Reads the trit as a boolean, `Pos` is true, `Neg` is false and `Zero` is unknown.
+    pub fn to_bool(self) -> Option<bool> {
+        match self {
+            Trit::Neg => Some(false),
+            Trit::Zero => None,
+            Trit::Pos => Some(true),
+        }
+    }
+}
+
+/// Every combination of `K` trits, all `3^K` of them, for exhaustively testing `K` input gates.
+/// Counts up in base 3 with element 0 changing fastest, starting from all `Neg`.
+///
+/// ```
+/// use bternary::trit::all_trit_tuples;
+/// use bternary::Trit;
+///
+/// for [a, b] in all_trit_tuples::<2>() {
+///     assert_eq!(a * b, b * a);
+/// }
+/// ```
+pub fn all_trit_tuples<const K: usize>() -> impl Iterator<Item = [Trit; K]> {
+    const TRITS: [Trit; 3] = [Trit::Neg, Trit::Zero, Trit::Pos];
+    (0..3usize.pow(K as u32)).map(|mut n| {
+        std::array::from_fn(|_| {
+            let trit = TRITS[n % 3];
+            n /= 3;
+            trit
+        })
+    })
+}
+
+/// `true` is `Pos` and `false` is `Neg`, leaving `Zero` for unknown.
+impl From<bool> for Trit {
+    fn from(value: bool) -> Self {
+        if value { Trit::Pos } else { Trit::Neg }
+    }
+}
+
+/// The carry out of an addition, passed between trit positions and between chained Words.
+/// It is always -1, 0 or 1 so it wraps a `Trit`, and compares equal to the trit it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Carry(Trit);
+
+impl Carry {
+    pub const ZERO: Carry = Carry(Trit::Zero);
+
+    pub fn new(trit: Trit) -> Self {
+        Carry(trit)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == Trit::Zero
+    }
+
+    pub fn into_trit(self) -> Trit {
+        self.0
+    }
+}
+
+impl Default for Carry {
+    fn default() -> Self {
+        Carry::ZERO
+    }
+}
+
+impl From<Trit> for Carry {
+    fn from(trit: Trit) -> Self {
+        Carry(trit)
+    }
+}
+
+impl From<Carry> for Trit {
+    fn from(carry: Carry) -> Self {
+        carry.0
+    }
+}
+
+impl PartialEq<Trit> for Carry {
+    fn eq(&self, other: &Trit) -> bool {
+        self.0 == *other
+    }
 }
 
 impl Neg for Trit {
@@ -56,8 +139,8 @@ impl Mul for Trit {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct InvalidTritValueError(i8);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTritValueError(pub i8);
 
 impl std::fmt::Display for InvalidTritValueError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -106,19 +189,27 @@ impl Ord for Trit {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_all_trit_tuples() {
+        assert_eq!(all_trit_tuples::<3>().count(), 27);
+        assert_eq!(all_trit_tuples::<0>().collect::<Vec<_>>(), [[]]);
+        let pairs: Vec<[Trit; 2]> = all_trit_tuples().collect();
+        assert_eq!(pairs[0], [Trit::Neg, Trit::Neg]);
+        assert_eq!(pairs[1], [Trit::Zero, Trit::Neg]);
+        assert_eq!(pairs[8], [Trit::Pos, Trit::Pos]);
+        for (i, a) in pairs.iter().enumerate() {
+            assert!(pairs[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
     #[test]
     fn test_full_add_exhaustive() {
-        let trits = [Trit::Neg, Trit::Zero, Trit::Pos];
         // Test all 3*3*3 = 27 possible combinations
-        for &a in &trits {
-            for &b in &trits {
-                for &c in &trits {
-                    let sum = a as i8 + b as i8 + c as i8;
-                    let (result, carry) = a.full_add(b, c);
-                    let check_sum = result as i8 + (carry as i8 * 3);
-                    assert_eq!(sum, check_sum, "Failed for inputs: {:?}, {:?}, {:?}", a, b, c);
-                }
-            }
+        for [a, b, c] in all_trit_tuples() {
+            let sum = a as i8 + b as i8 + c as i8;
+            let (result, carry) = a.full_add(b, c);
+            let check_sum = result as i8 + (carry as i8 * 3);
+            assert_eq!(sum, check_sum, "Failed for inputs: {:?}, {:?}, {:?}", a, b, c);
         }
 
         // Spot check a few key cases for clarity
@@ -159,6 +250,29 @@ mod tests {
         assert_eq!(Trit::Zero * Trit::Pos, Trit::Zero);
     }
 
+    #[test]
+    fn test_carry() {
+        assert!(Carry::ZERO.is_zero());
+        assert_eq!(Carry::default(), Carry::ZERO);
+        let carry = Carry::from(Trit::Neg);
+        assert!(!carry.is_zero());
+        assert_eq!(carry, Trit::Neg);
+        assert_eq!(carry.into_trit(), Trit::Neg);
+        assert_eq!(Trit::from(Carry::new(Trit::Pos)), Trit::Pos);
+    }
+
+    #[test]
+    fn test_bool_conversions() {
+        assert_eq!(Trit::from(true), Trit::Pos);
+        assert_eq!(Trit::from(false), Trit::Neg);
+        assert_eq!(Trit::Pos.to_bool(), Some(true));
+        assert_eq!(Trit::Neg.to_bool(), Some(false));
+        assert_eq!(Trit::Zero.to_bool(), None);
+        for value in [true, false] {
+            assert_eq!(Trit::from(value).to_bool(), Some(value));
+        }
+    }
+
     #[test]
     fn test_try_from_i8() {
         // Valid cases
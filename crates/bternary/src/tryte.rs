@@ -1,9 +1,90 @@
-use crate::balanced_int::{TernaryIntegerRepr, BalancedInt};
+use thiserror::Error;
+use crate::balanced_int::{balanced_range, TernaryIntegerRepr, BalancedInt};
 
 pub const TRITS_IN_TRYTE: usize = 6;
 
+/// Largest tryte value, (3^6 - 1) / 2.
+const TRYTE_MAX: i16 = balanced_range(TRITS_IN_TRYTE as u32).1 as i16;
+
+#[cfg(not(feature = "packed-tryte"))]
 pub type Tryte = BalancedInt<TRITS_IN_TRYTE>;
 
-impl TernaryIntegerRepr for Tryte {
+#[cfg(feature = "packed-tryte")]
+pub use crate::packed::Tryte;
+
+impl TernaryIntegerRepr for BalancedInt<TRITS_IN_TRYTE> {
     type Int = i16;
-}
\ No newline at end of file
+}
+
+impl Tryte {
+    /// The character with this code point. Only `0..=364` maps to a character,
+    /// which covers ASCII and Latin-1, so negative trytes give `None`.
+    pub fn to_char(&self) -> Option<char> {
+        u32::try_from(self.to_int()).ok().and_then(char::from_u32)
+    }
+
+    /// Stores a character as its code point, `None` for code points above 364.
+    pub fn from_char(c: char) -> Option<Tryte> {
+        i16::try_from(u32::from(c)).ok().and_then(|value| Tryte::try_from(value).ok())
+    }
+}
+
+/// A value outside of the tryte range `-364..=364`.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("{0} is out of range for a tryte (-364..=364)")]
+pub struct TryteConversionError(pub i32);
+
+impl TryFrom<i16> for Tryte {
+    type Error = TryteConversionError;
+
+    /// Checked conversion, unlike `from_int` which wraps.
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        if (-TRYTE_MAX..=TRYTE_MAX).contains(&value) {
+            Ok(Tryte::from_int(value))
+        } else {
+            Err(TryteConversionError(value as i32))
+        }
+    }
+}
+
+impl TryFrom<u16> for Tryte {
+    type Error = TryteConversionError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        i16::try_from(value)
+            .map_err(|_| TryteConversionError(value as i32))
+            .and_then(Tryte::try_from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_i16_boundaries() {
+        assert_eq!(Tryte::try_from(364i16).unwrap().to_int(), 364);
+        assert_eq!(Tryte::try_from(-364i16).unwrap().to_int(), -364);
+        assert_eq!(Tryte::try_from(0i16).unwrap(), Tryte::zero());
+        assert_eq!(Tryte::try_from(365i16), Err(TryteConversionError(365)));
+        assert_eq!(Tryte::try_from(-365i16), Err(TryteConversionError(-365)));
+        assert_eq!(Tryte::try_from(i16::MIN), Err(TryteConversionError(i16::MIN as i32)));
+    }
+
+    #[test]
+    fn test_char_round_trip() {
+        let a = Tryte::from_char('A').unwrap();
+        assert_eq!(a.to_int(), 65);
+        assert_eq!(a.to_char(), Some('A'));
+        assert_eq!(Tryte::from_char('é').unwrap().to_char(), Some('é'));
+        assert_eq!(Tryte::from_char('€'), None);
+        assert_eq!(Tryte::from_int(-65).to_char(), None);
+    }
+
+    #[test]
+    fn test_try_from_u16() {
+        assert_eq!(Tryte::try_from(364u16).unwrap().to_int(), 364);
+        assert_eq!(Tryte::try_from(365u16), Err(TryteConversionError(365)));
+        assert_eq!(Tryte::try_from(u16::MAX), Err(TryteConversionError(65535)));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::balanced_int::{TernaryIntegerRepr, BalancedInt, ArithmeticTernaryInteger};
+use crate::balanced_int::{balanced_range, pow3, TernaryIntegerRepr, BalancedInt, ArithmeticTernaryInteger, BIntError};
+use crate::tryte::{Tryte, TryteConversionError, TRITS_IN_TRYTE};
+use crate::Trit;
 
 pub const TRYTES_IN_WORD: usize = 4;
 const TRITS_IN_WORD: usize = crate::tryte::TRITS_IN_TRYTE * TRYTES_IN_WORD;
@@ -11,16 +13,523 @@ impl TernaryIntegerRepr for Word {
 
 impl ArithmeticTernaryInteger for Word {}
 
+// Narrower and wider words, for modelling machines other than the 24 trit Triode.
+impl TernaryIntegerRepr for BalancedInt<18> {
+    type Int = i64;
+}
+
+impl ArithmeticTernaryInteger for BalancedInt<18> {}
+
+impl TernaryIntegerRepr for BalancedInt<36> {
+    type Int = i64;
+}
+
+impl ArithmeticTernaryInteger for BalancedInt<36> {}
+
+impl Word {
+    /// Splits the Word into its trytes, tryte 0 holding the least significant trits.
+    pub fn to_trytes(&self) -> [Tryte; TRYTES_IN_WORD] {
+        std::array::from_fn(|t| {
+            Tryte::new(std::array::from_fn(|i| self[t * TRITS_IN_TRYTE + i]))
+        })
+    }
+
+    /// Joins trytes back into a Word, tryte 0 becoming the least significant trits.
+    pub fn from_trytes(trytes: [Tryte; TRYTES_IN_WORD]) -> Self {
+        let mut trits = [Trit::Zero; TRITS_IN_WORD];
+        for (t, tryte) in trytes.iter().enumerate() {
+            for (i, &trit) in tryte.iter().enumerate() {
+                trits[t * TRITS_IN_TRYTE + i] = trit;
+            }
+        }
+        Word::new(trits)
+    }
+
+    /// Joins tryte values, each checked against the tryte range, with `vals[0]` becoming the
+    /// least significant tryte. Errors with the first value out of range.
+    pub fn from_tryte_values(vals: [i16; TRYTES_IN_WORD]) -> Result<Self, TryteConversionError> {
+        let mut trytes = [Tryte::zero(); TRYTES_IN_WORD];
+        for (tryte, value) in trytes.iter_mut().zip(vals) {
+            *tryte = Tryte::try_from(value)?;
+        }
+        Ok(Word::from_trytes(trytes))
+    }
+
+    /// Reverses the order of the four trytes, keeping the trits inside each tryte in place.
+    /// This is a layout operation for exchanging data with tryte big endian dumps:
+    /// the numeric value changes unless the Word is symmetric.
+    pub fn swap_tryte_order(&self) -> Self {
+        let mut trytes = self.to_trytes();
+        trytes.reverse();
+        Word::from_trytes(trytes)
+    }
+
+    /// A ternary parity trit: the trit `p` making the sum of every trit plus `p` a multiple of 3.
+    /// With `s` the trit sum, `p` is `-s mod 3` written balanced, so `s = 4` gives `p = -1`.
+    /// Changing any single trit moves the sum by 1 or 2, which `check_balance` always catches.
+    pub fn balance_trit(&self) -> Trit {
+        let sum: i64 = self.iter().map(|&t| t as i64).sum();
+        match (-sum).rem_euclid(3) {
+            0 => Trit::Zero,
+            1 => Trit::Pos,
+            _ => Trit::Neg,
+        }
+    }
+
+    /// True when `parity` is the `balance_trit` of this Word.
+    pub fn check_balance(&self, parity: Trit) -> bool {
+        self.balance_trit() == parity
+    }
+
+    /// The smallest multiple of `3^trit_power` at or above the value, rounding toward positive
+    /// infinity. Wraps past `MAX` near the top. Panics unless `trit_power` is below 24.
+    pub fn align_up(&self, trit_power: u32) -> Self {
+        let (nearest, step) = self.nearest_multiple(trit_power);
+        if nearest < *self { nearest + step } else { nearest }
+    }
+
+    /// The largest multiple of `3^trit_power` at or below the value, rounding toward negative
+    /// infinity. Wraps past `MIN` near the bottom. Panics unless `trit_power` is below 24.
+    pub fn align_down(&self, trit_power: u32) -> Self {
+        let (nearest, step) = self.nearest_multiple(trit_power);
+        if nearest > *self { nearest - step } else { nearest }
+    }
+
+    /// The multiple of `3^trit_power` nearest the value, which is `shift_right` then `shift_left`,
+    /// along with `3^trit_power` itself.
+    fn nearest_multiple(&self, trit_power: u32) -> (Self, Self) {
+        let amt = trit_power as usize;
+        assert!(amt < TRITS_IN_WORD, "3^{trit_power} doesn't fit in a Word");
+        (self.shift_right(amt).shift_left(amt), Word::from_int(pow3(trit_power)))
+    }
+
+    /// The Word with the value of standard base 3 `digits` (each 0, 1 or 2), `digits[0]` being
+    /// the least significant. Leading zeros are allowed, the value has to fit a Word.
+    pub fn from_unbalanced_base3(digits: &[u8]) -> Result<Self, BIntError> {
+        let mut value: i64 = 0;
+        for &digit in digits.iter().rev() {
+            if digit >= 3 {
+                return Err(BIntError::InvalidDigit(digit));
+            }
+            value = value.checked_mul(3)
+                .and_then(|v| v.checked_add(digit as i64))
+                .ok_or(BIntError::ValueDoesNotFit(0, TRITS_IN_WORD - 1))?;
+        }
+        Word::from_int_checked(value)
+    }
+
+    /// The value as standard base 3 digits, least significant first and without leading zeros,
+    /// so zero is `[0]`. Negative values have no such digits and error.
+    pub fn to_unbalanced_base3(&self) -> Result<Vec<u8>, BIntError> {
+        let mut value = self.to_int();
+        if value < 0 {
+            return Err(BIntError::NegativeUnbalanced(value));
+        }
+        let mut digits = Vec::new();
+        loop {
+            digits.push((value % 3) as u8);
+            value /= 3;
+            if value == 0 {
+                return Ok(digits);
+            }
+        }
+    }
+
+    /// A 64 bit hash of the trit pattern, seeded so that each seed gives an independent looking
+    /// function, as a bloom filter needs. Unlike a std `Hash` the output is fixed, so it is the
+    /// same across runs, platforms and versions.
+    ///
+    /// The trits are packed 2 bits each into the low 48 bits (`Neg` 00, `Zero` 01, `Pos` 10),
+    /// xored with the mixed seed and mixed again. The mixer is the splitmix64 finalizer, which
+    /// flips each output bit with probability close to 1/2 for any single flipped input bit.
+    pub fn hash_with_seed(&self, seed: u64) -> u64 {
+        let packed = self.iter().rev().fold(0u64, |acc, &t| acc << 2 | (t as i8 + 1) as u64);
+        splitmix64(packed ^ splitmix64(seed))
+    }
+}
+
+/// One step of the splitmix64 generator: add the golden ratio increment then finalize.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Zero extends the tryte into the low trits of a Word.
+impl From<Tryte> for Word {
+    fn from(tryte: Tryte) -> Self {
+        let mut trytes = [Tryte::zero(); TRYTES_IN_WORD];
+        trytes[0] = tryte;
+        Word::from_trytes(trytes)
+    }
+}
+
+/// Narrows a Word to its low tryte, erroring unless every higher trit is zero.
+impl TryFrom<Word> for Tryte {
+    type Error = BIntError;
+
+    fn try_from(word: Word) -> Result<Self, Self::Error> {
+        if word.iter().skip(TRITS_IN_TRYTE).any(|&t| t != Trit::Zero) {
+            return Err(BIntError::ValueDoesNotFit(0, TRITS_IN_TRYTE - 1));
+        }
+        Ok(word.to_trytes()[0])
+    }
+}
+
+/// Lossless conversions from integer types whose whole range fits in a Word.
+/// `i64` can overflow a Word so it only gets the checked `TryFrom`.
+macro_rules! impl_word_from_small_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Word {
+                fn from(value: $int) -> Self {
+                    Word::from_int(i64::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_word_from_small_int!(i8, i16, i32);
+
+/// Converts every value that fits in a Word.
+/// Returns the converted Words in order and the `(index, value)` of every value that didn't fit.
+pub fn words_from_ints(values: &[i64]) -> (Vec<Word>, Vec<(usize, i64)>) {
+    let mut words = Vec::with_capacity(values.len());
+    let mut rejected = Vec::new();
+    for (index, &value) in values.iter().enumerate() {
+        match Word::try_from(value) {
+            Ok(word) => words.push(word),
+            Err(_) => rejected.push((index, value)),
+        }
+    }
+    (words, rejected)
+}
+
+/// Element wise `a[i] + b[i]`, wrapping like `Add`.
+/// Errors if the slices have different lengths.
+pub fn add_slices(a: &[Word], b: &[Word]) -> Result<Vec<Word>, BIntError> {
+    let mut out = Vec::with_capacity(a.len());
+    add_slices_into(a, b, &mut out)?;
+    Ok(out)
+}
+
+/// `add_slices` writing into `out`, which is cleared first so its allocation can be reused.
+pub fn add_slices_into(a: &[Word], b: &[Word], out: &mut Vec<Word>) -> Result<(), BIntError> {
+    if a.len() != b.len() {
+        return Err(BIntError::LengthMismatch(a.len(), b.len()));
+    }
+    out.clear();
+    out.extend(a.iter().zip(b).map(|(&x, &y)| x + y));
+    Ok(())
+}
+
+/// Parallel `add_slices` using rayon, enabled with the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn add_slices_par(a: &[Word], b: &[Word]) -> Result<Vec<Word>, BIntError> {
+    use rayon::prelude::*;
+
+    if a.len() != b.len() {
+        return Err(BIntError::LengthMismatch(a.len(), b.len()));
+    }
+    Ok(a.par_iter().zip(b.par_iter()).map(|(&x, &y)| x + y).collect())
+}
+
+/// The running total after each word, so `out[i]` is `words[0] + ... + words[i]`.
+/// Each step wraps like `Add`, so a total past the Word range wraps around to the other end
+/// and keeps accumulating from there.
+pub fn prefix_sums(words: &[Word]) -> Vec<Word> {
+    words.iter()
+        .scan(Word::zero(), |total, &w| {
+            *total += w;
+            Some(*total)
+        })
+        .collect()
+}
+
+/// Evaluates `coeffs[0] + coeffs[1] * x + coeffs[2] * x^2 + ...` with Horner's method.
+/// Every step wraps like `Add` and `Mul`, so the result is the true value reduced into the Word
+/// range even when intermediate powers of `x` would overflow. No coefficients evaluates to zero.
+pub fn eval_poly(coeffs: &[Word], x: Word) -> Word {
+    coeffs.iter().rev().fold(Word::zero(), |acc, &c| acc * x + c)
+}
+
+/// Rotation applied to the running checksum before each word is added.
+const CHECKSUM_ROTATION: usize = 5;
+
+/// A stable, non cryptographic checksum for detecting corrupted data.
+///
+/// Starting from `c = 1`, each word updates `c = c.rotate_left(5) + word` with wrapping `Add`,
+/// and the final `c` is the checksum. Both steps are bijections of the running value, so
+/// changing any single word always changes the result, and the rotation makes it depend on
+/// word order. The nonzero start makes leading zero words count too. This algorithm is fixed,
+/// checksums stay comparable across versions.
+pub fn ternary_checksum(words: &[Word]) -> Word {
+    words.iter().fold(Word::from_int(1), |c, &w| c.rotate_left(CHECKSUM_ROTATION) + w)
+}
+
+/// Approximates `value` by a fraction `(n, d)` with `0 < d <= max_den`, in lowest terms.
+///
+/// `value` is first rounded to `num / 3^k` with the largest `k` that keeps `num` in a Word,
+/// which is within `3^-23 / 2` for magnitudes below 1.5. Euclid's algorithm on `num` and `3^k`,
+/// using `checked_div_rem` adjusted to floor division, then gives the continued fraction, and
+/// the result is its last convergent whose denominator is at most `max_den`. Convergents
+/// alternate around the target and each is within `1 / (d * d')` of it, where `d'` is the
+/// next denominator, so the error is below `1 / (d * max_den)` on top of that first rounding.
+///
+/// A `max_den` below 1 is treated as 1. NaN gives `0 / 1`, and values beyond the Word range
+/// saturate to the largest Word over 1.
+pub fn rational_approx(value: f64, max_den: Word) -> (Word, Word) {
+    let word_max = balanced_range(TRITS_IN_WORD as u32).1;
+    let one = Word::from_int(1);
+    if value.is_nan() {
+        return (Word::zero(), one);
+    }
+    if value.abs() >= word_max as f64 {
+        return (Word::from_int(word_max * value.signum() as i64), one);
+    }
+
+    let mut den: i64 = 1;
+    while den * 3 <= word_max && value.abs() * (den * 3) as f64 <= word_max as f64 {
+        den *= 3;
+    }
+    let num = (value * den as f64).round() as i64;
+    if den == 1 {
+        return (Word::from_int(num), one);
+    }
+
+    let max_den = max_den.to_int().max(1) as i128;
+    // Convergents h/k, starting from the conventional h_-2/k_-2 = 0/1 and h_-1/k_-1 = 1/0.
+    let (mut h_prev, mut h) = (0i128, 1i128);
+    let (mut k_prev, mut k) = (1i128, 0i128);
+    let (mut p, mut q) = (Word::from_int(num), Word::from_int(den));
+    loop {
+        let (mut a, mut r) = p.checked_div_rem(q).expect("q stays positive");
+        // Balanced division rounds to nearest, continued fractions want the floor.
+        if r.sign() == Trit::Neg {
+            a -= one;
+            r += q;
+        }
+        let a = a.to_int() as i128;
+        let (h_next, k_next) = (a * h + h_prev, a * k + k_prev);
+        if k_next > max_den || h_next.abs() > word_max as i128 {
+            break;
+        }
+        (h_prev, h, k_prev, k) = (h, h_next, k, k_next);
+        if r.is_zero() {
+            break;
+        }
+        (p, q) = (q, r);
+    }
+    (Word::from_int(h as i64), Word::from_int(k as i64))
+}
+
+/// Smallest Word in the slice, or `None` if it is empty.
+/// `Ord` on balanced ternary compares trits from the most significant end, which matches
+/// numeric order, so this also works for a min heap via `BinaryHeap<std::cmp::Reverse<Word>>`.
+pub fn min_word(words: &[Word]) -> Option<Word> {
+    words.iter().min().copied()
+}
+
+/// Largest Word in the slice, or `None` if it is empty.
+pub fn max_word(words: &[Word]) -> Option<Word> {
+    words.iter().max().copied()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Trit;
 
     const TESTING_TRITS: [Trit; 24] = [Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Pos, Trit::Zero,
     Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Pos, Trit::Zero,
     Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Pos, Trit::Zero,
     Trit::Neg, Trit::Zero, Trit::Pos, Trit::Neg, Trit::Pos, Trit::Zero];
 
+    #[test]
+    fn test_tryte_word_conversions() {
+        let tryte = Tryte::from_int(200);
+        let word = Word::from(tryte);
+        assert_eq!(word.to_int(), 200);
+        assert_eq!(Tryte::try_from(word), Ok(tryte));
+        assert_eq!(Word::from(Tryte::from_int(-364)).to_int(), -364);
+
+        assert_eq!(Tryte::try_from(Word::from_int(500)), Err(BIntError::ValueDoesNotFit(0, 5)));
+        assert!(Tryte::try_from(Word::from_int(-365)).is_err());
+    }
+
+    #[test]
+    fn test_eval_poly() {
+        // 3 - 2x + x^3
+        let coeffs = [3, -2, 0, 1].map(Word::from_int);
+        for (x, expected) in [(0, 3), (1, 2), (2, 7), (-3, -18), (10, 983)] {
+            assert_eq!(eval_poly(&coeffs, Word::from_int(x)).to_int(), expected, "x = {x}");
+        }
+        assert!(eval_poly(&[], Word::from_int(5)).is_zero());
+    }
+
+    #[test]
+    fn test_from_tryte_values() {
+        let word = Word::from_tryte_values([5, 0, -1, 364]).unwrap();
+        assert_eq!(word.to_trytes().map(|t| t.to_int()), [5, 0, -1, 364]);
+        assert_eq!(word.to_int(), 5 - 729i64.pow(2) + 364 * 729i64.pow(3));
+        assert_eq!(Word::from_tryte_values([0; 4]).unwrap(), Word::zero());
+        assert_eq!(Word::from_tryte_values([1, 365, -400, 0]), Err(TryteConversionError(365)));
+    }
+
+    #[test]
+    fn test_balance_trit() {
+        assert_eq!(Word::zero().balance_trit(), Trit::Zero);
+        // 11 = 11T sums to 1, 4 = 11 sums to 2.
+        assert_eq!(Word::from_int(11).balance_trit(), Trit::Neg);
+        assert_eq!(Word::from_int(4).balance_trit(), Trit::Pos);
+        let word = Word::new(TESTING_TRITS);
+        let parity = word.balance_trit();
+        assert!(word.check_balance(parity));
+        for i in 0..24 {
+            for trit in [Trit::Neg, Trit::Zero, Trit::Pos] {
+                if trit == word[i] {
+                    continue;
+                }
+                let mut corrupted = word;
+                corrupted[i] = trit;
+                assert!(!corrupted.check_balance(parity), "trit {i} set to {trit}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_align() {
+        for (value, up3, down3, up9, down9) in [
+            (0, 0, 0, 0, 0),
+            (7, 9, 6, 9, 0),
+            (5, 6, 3, 9, 0),
+            (9, 9, 9, 9, 9),
+            (-7, -6, -9, 0, -9),
+            (-4, -3, -6, 0, -9),
+            (13, 15, 12, 18, 9),
+        ] {
+            let word = Word::from_int(value);
+            assert_eq!(word.align_up(1).to_int(), up3, "{value} up to 3");
+            assert_eq!(word.align_down(1).to_int(), down3, "{value} down to 3");
+            assert_eq!(word.align_up(2).to_int(), up9, "{value} up to 9");
+            assert_eq!(word.align_down(2).to_int(), down9, "{value} down to 9");
+        }
+        assert_eq!(Word::from_int(-5).align_up(0).to_int(), -5);
+        assert_eq!(Word::MAX.align_down(23).to_int(), pow3(23));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_align_too_far_panics() {
+        Word::from_int(1).align_up(24);
+    }
+
+    #[test]
+    fn test_unbalanced_base3() {
+        // 2 + 0*3 + 1*9 = 11
+        assert_eq!(Word::from_unbalanced_base3(&[2, 0, 1]).unwrap().to_int(), 11);
+        assert_eq!(Word::from_int(11).to_unbalanced_base3(), Ok(vec![2, 0, 1]));
+        assert_eq!(Word::from_unbalanced_base3(&[2, 0, 1, 0, 0]).unwrap().to_int(), 11);
+        assert_eq!(Word::zero().to_unbalanced_base3(), Ok(vec![0]));
+        assert_eq!(Word::from_unbalanced_base3(&[]), Ok(Word::zero()));
+        for value in [0, 1, 2, 3, 364, 123_456_789, 141_214_768_240] {
+            let digits = Word::from_int(value).to_unbalanced_base3().unwrap();
+            assert_eq!(Word::from_unbalanced_base3(&digits).unwrap().to_int(), value);
+        }
+
+        assert_eq!(Word::from_unbalanced_base3(&[1, 3]), Err(BIntError::InvalidDigit(3)));
+        assert_eq!(Word::from_int(-5).to_unbalanced_base3(), Err(BIntError::NegativeUnbalanced(-5)));
+        // 3^24 - 1 is past the Word range, and 3^60 past i64.
+        assert_eq!(Word::from_unbalanced_base3(&[2; 24]), Err(BIntError::ValueDoesNotFit(0, 23)));
+        assert_eq!(Word::from_unbalanced_base3(&[1; 60]), Err(BIntError::ValueDoesNotFit(0, 23)));
+    }
+
+    #[test]
+    fn test_hash_with_seed_distribution() {
+        const SAMPLES: u32 = 20_000;
+        let mut ones = [0u32; 64];
+        let mut agreeing = 0u32;
+        // Consecutive values differ in few trits, the hardest input for a weak mixer.
+        for value in 0..SAMPLES as i64 {
+            let word = Word::from_int(value - 10_000);
+            let (a, b) = (word.hash_with_seed(1), word.hash_with_seed(2));
+            assert_eq!(a, word.hash_with_seed(1));
+            for (bit, count) in ones.iter_mut().enumerate() {
+                *count += (a >> bit & 1) as u32;
+            }
+            agreeing += (!(a ^ b)).count_ones();
+        }
+        // Each bit should be set half the time, 10_000 with a standard deviation of about 71.
+        for count in ones {
+            assert!((9_500..=10_500).contains(&count), "bit set {count} times");
+        }
+        // Independent seeds agree on half of all bits.
+        let total = SAMPLES * 64;
+        assert!((agreeing as f64 / total as f64 - 0.5).abs() < 0.01);
+        assert_ne!(Word::zero().hash_with_seed(0), Word::zero().hash_with_seed(1));
+    }
+
+    #[test]
+    fn test_rational_approx() {
+        let approx = |value: f64, max_den: i64| {
+            let (n, d) = rational_approx(value, Word::from_int(max_den));
+            (n.to_int(), d.to_int())
+        };
+        assert_eq!(approx(1.0 / 3.0, 100), (1, 3));
+        assert_eq!(approx(0.1, 100), (1, 10));
+        assert_eq!(approx(-0.75, 100), (-3, 4));
+        assert_eq!(approx(std::f64::consts::PI, 1000), (355, 113));
+        assert_eq!(approx(std::f64::consts::PI, 10), (22, 7));
+        assert_eq!(approx(2.6, 1), (3, 1));
+        assert_eq!(approx(f64::NAN, 10), (0, 1));
+        assert_eq!(approx(1e300, 10), (141_214_768_240, 1));
+
+        for (value, max_den) in [(0.1, 7), (std::f64::consts::E, 50), (-1.234_567, 1_000), (1e-3, 20_000)] {
+            let (n, d) = approx(value, max_den);
+            assert!(0 < d && d <= max_den);
+            let error = (value - n as f64 / d as f64).abs();
+            assert!(error < 1.0 / (d * max_den) as f64 + 1e-10, "{value} ~ {n}/{d}, error {error}");
+        }
+    }
+
+    #[test]
+    fn test_ternary_checksum() {
+        let words: Vec<Word> = [5, -42, 0, 141_214_768_240, 7].map(Word::from_int).to_vec();
+        let checksum = ternary_checksum(&words);
+        assert_eq!(ternary_checksum(&words), checksum);
+        // Pinned so the algorithm can't change silently: the start value 1 rotated by 5 trits is 3^5.
+        assert_eq!(ternary_checksum(&[]).to_int(), 1);
+        assert_eq!(ternary_checksum(&[Word::from_int(2)]).to_int(), 243 + 2);
+
+        for w in 0..words.len() {
+            for t in 0..24 {
+                let mut corrupted = words.clone();
+                corrupted[w][t] = -corrupted[w][t];
+                if corrupted[w][t] == Trit::Zero {
+                    corrupted[w][t] = Trit::Pos;
+                }
+                assert_ne!(ternary_checksum(&corrupted), checksum, "word {w} trit {t}");
+            }
+        }
+
+        let mut swapped = words.clone();
+        swapped.swap(0, 1);
+        assert_ne!(ternary_checksum(&swapped), checksum);
+        assert_ne!(ternary_checksum(&[Word::zero()]), ternary_checksum(&[]));
+    }
+
+    #[test]
+    fn test_from_small_ints() {
+        assert_eq!(Word::from(i8::MIN).to_int(), -128);
+        assert_eq!(Word::from(i8::MAX).to_int(), 127);
+        assert_eq!(Word::from(i16::MIN).to_int(), -32_768);
+        assert_eq!(Word::from(i16::MAX).to_int(), 32_767);
+        assert_eq!(Word::from(i32::MIN).to_int(), -2_147_483_648);
+        assert_eq!(Word::from(i32::MAX).to_int(), 2_147_483_647);
+        assert_eq!(Word::from(42i32), Word::from_int(42));
+    }
+
     #[test]
     fn test_word_zero() {
         let word = Word::zero();
@@ -41,6 +550,107 @@ mod tests {
         assert_eq!(word, Word::new(TESTING_TRITS));
     }
 
+    #[test]
+    fn test_word_try_from_i64() {
+        assert_eq!(Word::try_from(141_214_768_240i64).unwrap().to_int(), 141_214_768_240);
+        assert_eq!(Word::try_from(-141_214_768_240i64).unwrap().to_int(), -141_214_768_240);
+        assert!(Word::try_from(141_214_768_241i64).is_err());
+        assert!(Word::try_from(i64::MIN).is_err());
+    }
+
+    #[test]
+    fn test_words_from_ints() {
+        let values = [5, 141_214_768_241, -12, i64::MAX, 0, -141_214_768_240];
+        let (words, rejected) = words_from_ints(&values);
+        let converted: Vec<i64> = words.iter().map(|w| w.to_int()).collect();
+        assert_eq!(converted, vec![5, -12, 0, -141_214_768_240]);
+        assert_eq!(rejected, vec![(1, 141_214_768_241), (3, i64::MAX)]);
+    }
+
+    #[test]
+    fn test_add_slices() {
+        let a: Vec<Word> = (-50..50).map(|v| Word::from_int(v * 1_234_567)).collect();
+        let b: Vec<Word> = (-50..50).map(|v| Word::from_int(v * -7_654)).collect();
+        let expected: Vec<Word> = a.iter().zip(&b).map(|(&x, &y)| x + y).collect();
+
+        assert_eq!(add_slices(&a, &b).unwrap(), expected);
+
+        let mut out = vec![Word::from_int(99); 3];
+        add_slices_into(&a, &b, &mut out).unwrap();
+        assert_eq!(out, expected);
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(add_slices_par(&a, &b).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_prefix_sums() {
+        let words: Vec<Word> = [1, 2, 3, -10, 40, 0].map(Word::from_int).to_vec();
+        let sums: Vec<i64> = prefix_sums(&words).iter().map(|w| w.to_int()).collect();
+        assert_eq!(sums, [1, 3, 6, -4, 36, 36]);
+        assert!(prefix_sums(&[]).is_empty());
+
+        let max = Word::from_int(141_214_768_240);
+        let sums: Vec<i64> = prefix_sums(&[max, Word::from_int(1), Word::from_int(5)])
+            .iter().map(|w| w.to_int()).collect();
+        assert_eq!(sums, [141_214_768_240, -141_214_768_240, -141_214_768_235]);
+    }
+
+    #[test]
+    fn test_add_slices_length_mismatch() {
+        let a = [Word::zero(); 3];
+        let b = [Word::zero(); 2];
+        assert_eq!(add_slices(&a, &b), Err(BIntError::LengthMismatch(3, 2)));
+        let mut out = Vec::new();
+        assert_eq!(add_slices_into(&a, &b, &mut out), Err(BIntError::LengthMismatch(3, 2)));
+    }
+
+    #[test]
+    fn test_ord_matches_numeric_order() {
+        let values = [-141_214_768_240, -1000, -364, -13, -1, 0, 1, 2, 13, 364, 1000, 141_214_768_240];
+        for &a in &values {
+            for &b in &values {
+                assert_eq!(Word::from_int(a).cmp(&Word::from_int(b)), a.cmp(&b), "Ord for {} vs {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_max_word() {
+        let words: Vec<Word> = [7, -42, 0, 364, -3].iter().map(|&v| Word::from_int(v)).collect();
+        assert_eq!(min_word(&words).unwrap().to_int(), -42);
+        assert_eq!(max_word(&words).unwrap().to_int(), 364);
+        assert_eq!(min_word(&[]), None);
+        assert_eq!(max_word(&[]), None);
+
+        let mut heap: std::collections::BinaryHeap<_> = words.iter().map(|&w| std::cmp::Reverse(w)).collect();
+        assert_eq!(heap.pop().unwrap().0.to_int(), -42);
+    }
+
+    #[test]
+    fn test_trytes_round_trip() {
+        let word = Word::from_int(-98_765_432);
+        let trytes = word.to_trytes();
+        assert_eq!(Word::from_trytes(trytes), word);
+
+        let from_small = Word::from_trytes([Tryte::from_int(5), Tryte::zero(), Tryte::zero(), Tryte::zero()]);
+        assert_eq!(from_small.to_int(), 5);
+        let high = Word::from_trytes([Tryte::zero(), Tryte::from_int(1), Tryte::zero(), Tryte::zero()]);
+        assert_eq!(high.to_int(), 729);
+    }
+
+    #[test]
+    fn test_swap_tryte_order() {
+        let word = Word::from_int(5);
+        let swapped = word.swap_tryte_order();
+        assert_eq!(swapped.to_trytes()[3].to_int(), 5);
+        assert_eq!(swapped.to_int(), 5 * 729i64.pow(3));
+        assert_eq!(swapped.swap_tryte_order(), word);
+
+        let word = Word::from(TESTING_TRITS);
+        assert_eq!(word.swap_tryte_order().swap_tryte_order(), word);
+    }
+
     #[test]
     fn test_word_display() {
         let word = Word::from(TESTING_TRITS);
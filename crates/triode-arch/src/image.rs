@@ -0,0 +1,181 @@
+//! On-disk program images.
+//!
+//! An image is a header followed by the program words, all little endian:
+//!
+//! | bytes | contents                          |
+//! |-------|-----------------------------------|
+//! | 4     | magic, `b"TRIO"`                  |
+//! | 2     | format version, `u16`             |
+//! | 6     | entry point, a packed Word        |
+//! | 4     | word count, `u32`                 |
+//! | 6 * n | the words, packed                 |
+//!
+//! A packed Word takes 2 bits per trit with trit `i` in bits `2i..2i+2` of a 48 bit integer,
+//! each trit stored as `value + 1`. The code `0b11` is never written and rejected on read.
+
+use std::io::{Read, Write};
+use bternary::{Trit, Word};
+use thiserror::Error;
+
+pub const IMAGE_MAGIC: [u8; 4] = *b"TRIO";
+/// Bumped whenever the layout changes, images of any other version are rejected.
+pub const IMAGE_VERSION: u16 = 1;
+
+const WORD_TRITS: usize = 24;
+const PACKED_WORD_BYTES: usize = 6;
+
+#[derive(Error, Debug)]
+pub enum ImageError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("not a triode image, magic is {0:?}")]
+    BadMagic([u8; 4]),
+
+    #[error("unsupported image version {0}, expected {IMAGE_VERSION}")]
+    UnsupportedVersion(u16),
+
+    #[error("image has {0} words, more than the format can count")]
+    TooLarge(usize),
+
+    #[error("invalid trit encoding in the entry point")]
+    InvalidEntry,
+
+    #[error("invalid trit encoding in word {0}")]
+    InvalidWord(usize),
+}
+
+/// An assembled program and where to start running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriodeImage {
+    pub entry: Word,
+    pub words: Vec<Word>,
+}
+
+impl TriodeImage {
+    pub fn new(entry: Word, words: Vec<Word>) -> Self {
+        TriodeImage { entry, words }
+    }
+}
+
+/// Writes `image` in the current format version.
+pub fn write_image(w: &mut impl Write, image: &TriodeImage) -> Result<(), ImageError> {
+    let count = u32::try_from(image.words.len()).map_err(|_| ImageError::TooLarge(image.words.len()))?;
+    w.write_all(&IMAGE_MAGIC)?;
+    w.write_all(&IMAGE_VERSION.to_le_bytes())?;
+    w.write_all(&pack_word(&image.entry))?;
+    w.write_all(&count.to_le_bytes())?;
+    for word in &image.words {
+        w.write_all(&pack_word(word))?;
+    }
+    Ok(())
+}
+
+/// Reads an image, checking the magic and version before anything else.
+/// A stream that ends early is an `Io` error of kind `UnexpectedEof`.
+pub fn read_image(r: &mut impl Read) -> Result<TriodeImage, ImageError> {
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic)?;
+    if magic != IMAGE_MAGIC {
+        return Err(ImageError::BadMagic(magic));
+    }
+    let mut version = [0; 2];
+    r.read_exact(&mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version != IMAGE_VERSION {
+        return Err(ImageError::UnsupportedVersion(version));
+    }
+
+    let entry = read_word(r)?.ok_or(ImageError::InvalidEntry)?;
+    let mut count = [0; 4];
+    r.read_exact(&mut count)?;
+    let count = u32::from_le_bytes(count) as usize;
+
+    // The count isn't trusted for the allocation, a corrupt header shouldn't reserve gigabytes.
+    let mut words = Vec::new();
+    for i in 0..count {
+        words.push(read_word(r)?.ok_or(ImageError::InvalidWord(i))?);
+    }
+    Ok(TriodeImage { entry, words })
+}
+
+fn pack_word(word: &Word) -> [u8; PACKED_WORD_BYTES] {
+    let bits = word.iter().enumerate()
+        .fold(0u64, |bits, (i, &t)| bits | (((t as i8 + 1) as u64) << (2 * i)));
+    let mut bytes = [0; PACKED_WORD_BYTES];
+    bytes.copy_from_slice(&bits.to_le_bytes()[..PACKED_WORD_BYTES]);
+    bytes
+}
+
+/// Reads a packed word, `None` if it holds the unused trit code.
+fn read_word(r: &mut impl Read) -> Result<Option<Word>, ImageError> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes[..PACKED_WORD_BYTES])?;
+    let bits = u64::from_le_bytes(bytes);
+    let mut word = Word::zero();
+    for i in 0..WORD_TRITS {
+        word[i] = match (bits >> (2 * i)) & 0b11 {
+            0 => Trit::Neg,
+            1 => Trit::Zero,
+            2 => Trit::Pos,
+            _ => return Ok(None),
+        };
+    }
+    Ok(Some(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> TriodeImage {
+        let words = [0, 1, -1, 141_214_768_240, -141_214_768_240, 42].map(Word::from_int);
+        TriodeImage::new(Word::from_int(3), words.to_vec())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let image = image();
+        let mut buffer = Vec::new();
+        write_image(&mut buffer, &image).unwrap();
+        assert_eq!(buffer.len(), 16 + 6 * image.words.len());
+        assert_eq!(&buffer[..4], b"TRIO");
+        assert_eq!(read_image(&mut buffer.as_slice()).unwrap(), image);
+
+        let empty = TriodeImage::new(Word::zero(), Vec::new());
+        let mut buffer = Vec::new();
+        write_image(&mut buffer, &empty).unwrap();
+        assert_eq!(read_image(&mut buffer.as_slice()).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_rejects_bad_header() {
+        let mut buffer = Vec::new();
+        write_image(&mut buffer, &image()).unwrap();
+
+        let mut bad_magic = buffer.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(read_image(&mut bad_magic.as_slice()), Err(ImageError::BadMagic(m)) if &m == b"XRIO"));
+
+        let mut bad_version = buffer.clone();
+        bad_version[4..6].copy_from_slice(&2u16.to_le_bytes());
+        assert!(matches!(read_image(&mut bad_version.as_slice()), Err(ImageError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn test_rejects_corrupt_body() {
+        let mut buffer = Vec::new();
+        write_image(&mut buffer, &image()).unwrap();
+
+        let truncated = &buffer[..buffer.len() - 1];
+        assert!(matches!(
+            read_image(&mut &truncated[..]),
+            Err(ImageError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+
+        // Word 1 starts at byte 22, set its lowest trit to the unused code.
+        let mut bad_trit = buffer.clone();
+        bad_trit[22] |= 0b11;
+        assert!(matches!(read_image(&mut bad_trit.as_slice()), Err(ImageError::InvalidWord(1))));
+    }
+}
@@ -0,0 +1,606 @@
+use std::fmt::{Display, Formatter};
+use bternary::balanced_int::BalancedInt;
+use bternary::Word;
+use thiserror::Error;
+use crate::ArchError;
+use crate::instruction_set::{
+    immediate12_field, immediate18_field, Field, Immediate, InstructionSet, MachineWord, Register,
+    FUNCT_FIELD, MIN_WORD_TRITS, REGISTER_COUNT, SP,
+};
+use crate::opcode::{instruction_mnemonic, Format, Funct, OpCode, ILLEGAL_OPCODE};
+
+/// A decoded instruction of a machine with `TRITS` wide words, a Triode Word by default.
+/// Branch and jump immediates are offsets in words relative to the branch itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction<const TRITS: usize = 24> {
+    /// Encodes to the all zero word, so zeroed memory runs as a stream of `nop`s.
+    Nop,
+    Halt,
+    /// rd = rs1 + rs2
+    Add { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 - rs2
+    Sub { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 * rs2
+    Mul { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 / rs2
+    Div { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 % rs2
+    Rem { rd: Register, rs1: Register, rs2: Register },
+    /// rd = -1, 0 or 1 as rs1 is less than, equal to or greater than rs2
+    Tcmp { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 * 3^rs2, with the shift amount clamped to `0..=TRITS`
+    Shl { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 / 3^rs2 rounded to nearest, with the shift amount clamped to `0..=TRITS`
+    Shr { rd: Register, rs1: Register, rs2: Register },
+    /// rd = rs1 + imm
+    Addi { rd: Register, rs1: Register, imm: Immediate },
+    /// rd = mem[rs1 + imm]
+    Load { rd: Register, rs1: Register, imm: Immediate },
+    /// mem[rs1 + imm] = rs2
+    Store { rs1: Register, rs2: Register, imm: Immediate },
+    /// Loads `count` registers from rd up, rd = mem[rs1], rd + 1 = mem[rs1 + 1] and so on.
+    /// The count is encoded unsigned and the run can't go past r26, see `register_run`.
+    Ldm { rd: Register, rs1: Register, count: Immediate },
+    /// Stores `count` registers from rs2 up, mem[rs1] = rs2, mem[rs1 + 1] = rs2 + 1 and so on.
+    Stm { rs1: Register, rs2: Register, count: Immediate },
+    /// pc += imm if rs1 == rs2
+    Beq { rs1: Register, rs2: Register, imm: Immediate },
+    /// pc += imm if rs1 != rs2
+    Bne { rs1: Register, rs2: Register, imm: Immediate },
+    /// pc += imm if rs1 < rs2
+    Blt { rs1: Register, rs2: Register, imm: Immediate },
+    /// pc += imm
+    Jmp { imm: Immediate },
+    /// Pushes pc + 1 onto the stack at `sp`, then pc += imm
+    Call { imm: Immediate },
+    /// Pops the return address off the stack at `sp` into pc
+    Ret,
+    /// Returns from an interrupt handler to the interrupted pc and re-enables interrupts
+    Iret,
+    /// A word whose opcode field value `opcode` names no built-in instruction, left for an
+    /// emulator extension to interpret. `decode` never produces it.
+    Custom { opcode: i16, word: BalancedInt<TRITS> },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("invalid opcode field value {0}")]
+    InvalidOpcode(i64),
+    /// The word holds `ILLEGAL_OPCODE`, which is reserved to always fail.
+    #[error("reserved illegal opcode")]
+    IllegalOpcode,
+    /// The funct field holds a reserved value, or one the opcode doesn't take.
+    #[error("invalid funct {funct} for opcode {opcode}")]
+    InvalidFunct { opcode: OpCode, funct: i64 },
+    /// An `ldm` or `stm` count that runs past the last register.
+    #[error("{opcode} of {count} registers from {first} is out of range")]
+    InvalidRegisterRun { opcode: OpCode, first: Register, count: i64 },
+}
+
+/// The `count` registers from `first` up that `ldm` and `stm` transfer, in order.
+/// `None` if `count` is negative or the run goes past the last register.
+pub fn register_run(first: Register, count: Immediate) -> Option<impl Iterator<Item = Register>> {
+    let end = first.0 as i64 + count.0;
+    (count.0 >= 0 && end <= REGISTER_COUNT as i64).then(|| (first.0..end as u8).map(Register))
+}
+
+impl<const TRITS: usize> Instruction<TRITS> {
+    /// The built-in opcode, `None` for `Custom`.
+    pub fn opcode(&self) -> Option<OpCode> {
+        Some(match self {
+            Instruction::Nop => OpCode::NOP,
+            Instruction::Halt => OpCode::HALT,
+            Instruction::Add { .. } => OpCode::ADD,
+            Instruction::Sub { .. } => OpCode::SUB,
+            Instruction::Mul { .. } => OpCode::MUL,
+            Instruction::Div { .. } => OpCode::DIV,
+            Instruction::Rem { .. } => OpCode::REM,
+            Instruction::Tcmp { .. } => OpCode::TCMP,
+            Instruction::Shl { .. } | Instruction::Shr { .. } => OpCode::SHIFT,
+            Instruction::Addi { .. } => OpCode::ADDI,
+            Instruction::Load { .. } => OpCode::LOAD,
+            Instruction::Store { .. } => OpCode::STORE,
+            Instruction::Ldm { .. } => OpCode::LDM,
+            Instruction::Stm { .. } => OpCode::STM,
+            Instruction::Beq { .. } => OpCode::BEQ,
+            Instruction::Bne { .. } => OpCode::BNE,
+            Instruction::Blt { .. } => OpCode::BLT,
+            Instruction::Jmp { .. } => OpCode::JMP,
+            Instruction::Call { .. } => OpCode::CALL,
+            Instruction::Ret => OpCode::RET,
+            Instruction::Iret => OpCode::IRET,
+            Instruction::Custom { .. } => return None,
+        })
+    }
+
+    /// Which of the instructions sharing `opcode()` this is.
+    pub fn funct(&self) -> Funct {
+        match self {
+            Instruction::Shl { .. } => Funct::Left,
+            Instruction::Shr { .. } => Funct::Right,
+            _ => Funct::Base,
+        }
+    }
+
+    /// The assembler mnemonic. `Custom` words have none and print as `.word` data.
+    pub fn mnemonic(&self) -> &'static str {
+        self.opcode().map_or(".word", |opcode| instruction_mnemonic(opcode, self.funct()))
+    }
+
+    /// What the instruction does with its operands filled in, e.g. `r1 = r2 + r3` for
+    /// `add r1, r2, r3`. The semantics match the variant docs.
+    pub fn describe(&self) -> String {
+        match *self {
+            Instruction::Nop => "no operation".to_string(),
+            Instruction::Halt => "stop the CPU".to_string(),
+            Instruction::Add { rd, rs1, rs2 } => format!("{rd} = {rs1} + {rs2}"),
+            Instruction::Sub { rd, rs1, rs2 } => format!("{rd} = {rs1} - {rs2}"),
+            Instruction::Mul { rd, rs1, rs2 } => format!("{rd} = {rs1} * {rs2}"),
+            Instruction::Div { rd, rs1, rs2 } => format!("{rd} = {rs1} / {rs2}"),
+            Instruction::Rem { rd, rs1, rs2 } => format!("{rd} = {rs1} % {rs2}"),
+            Instruction::Tcmp { rd, rs1, rs2 } => format!("{rd} = compare({rs1}, {rs2})"),
+            Instruction::Shl { rd, rs1, rs2 } => format!("{rd} = {rs1} * 3^{rs2}"),
+            Instruction::Shr { rd, rs1, rs2 } => format!("{rd} = {rs1} / 3^{rs2}"),
+            Instruction::Addi { rd, rs1, imm } => format!("{rd} = {rs1} + {}", imm.0),
+            Instruction::Load { rd, rs1, imm } => format!("{rd} = mem[{rs1} + {}]", imm.0),
+            Instruction::Store { rs1, rs2, imm } => format!("mem[{rs1} + {}] = {rs2}", imm.0),
+            Instruction::Ldm { rd, rs1, count } => {
+                format!("{} registers from {rd} = mem[{rs1}..]", count.0)
+            }
+            Instruction::Stm { rs1, rs2, count } => {
+                format!("mem[{rs1}..] = {} registers from {rs2}", count.0)
+            }
+            Instruction::Beq { rs1, rs2, imm } => format!("pc += {} if {rs1} == {rs2}", imm.0),
+            Instruction::Bne { rs1, rs2, imm } => format!("pc += {} if {rs1} != {rs2}", imm.0),
+            Instruction::Blt { rs1, rs2, imm } => format!("pc += {} if {rs1} < {rs2}", imm.0),
+            Instruction::Jmp { imm } => format!("pc += {}", imm.0),
+            Instruction::Call { imm } => format!("push pc + 1, pc += {}", imm.0),
+            Instruction::Ret => "pop pc".to_string(),
+            Instruction::Iret => "pc = interrupted pc, enable interrupts".to_string(),
+            Instruction::Custom { opcode, .. } => format!("custom opcode {opcode}"),
+        }
+    }
+
+    /// The same instruction for a machine with `M` trit words. `Custom` words are truncated or
+    /// zero extended, see `BalancedInt::resize`.
+    pub fn resize<const M: usize>(self) -> Instruction<M> {
+        match self {
+            Instruction::Nop => Instruction::Nop,
+            Instruction::Halt => Instruction::Halt,
+            Instruction::Add { rd, rs1, rs2 } => Instruction::Add { rd, rs1, rs2 },
+            Instruction::Sub { rd, rs1, rs2 } => Instruction::Sub { rd, rs1, rs2 },
+            Instruction::Mul { rd, rs1, rs2 } => Instruction::Mul { rd, rs1, rs2 },
+            Instruction::Div { rd, rs1, rs2 } => Instruction::Div { rd, rs1, rs2 },
+            Instruction::Rem { rd, rs1, rs2 } => Instruction::Rem { rd, rs1, rs2 },
+            Instruction::Tcmp { rd, rs1, rs2 } => Instruction::Tcmp { rd, rs1, rs2 },
+            Instruction::Shl { rd, rs1, rs2 } => Instruction::Shl { rd, rs1, rs2 },
+            Instruction::Shr { rd, rs1, rs2 } => Instruction::Shr { rd, rs1, rs2 },
+            Instruction::Addi { rd, rs1, imm } => Instruction::Addi { rd, rs1, imm },
+            Instruction::Load { rd, rs1, imm } => Instruction::Load { rd, rs1, imm },
+            Instruction::Store { rs1, rs2, imm } => Instruction::Store { rs1, rs2, imm },
+            Instruction::Ldm { rd, rs1, count } => Instruction::Ldm { rd, rs1, count },
+            Instruction::Stm { rs1, rs2, count } => Instruction::Stm { rs1, rs2, count },
+            Instruction::Beq { rs1, rs2, imm } => Instruction::Beq { rs1, rs2, imm },
+            Instruction::Bne { rs1, rs2, imm } => Instruction::Bne { rs1, rs2, imm },
+            Instruction::Blt { rs1, rs2, imm } => Instruction::Blt { rs1, rs2, imm },
+            Instruction::Jmp { imm } => Instruction::Jmp { imm },
+            Instruction::Call { imm } => Instruction::Call { imm },
+            Instruction::Ret => Instruction::Ret,
+            Instruction::Iret => Instruction::Iret,
+            Instruction::Custom { opcode, word } => Instruction::Custom { opcode, word: word.resize() },
+        }
+    }
+
+    /// The register the instruction writes, `r0` included even though the write is discarded.
+    /// `call` and `ret` write `sp`, and `ldm` is reported by the first register of its run.
+    pub fn destination(&self) -> Option<Register> {
+        match *self {
+            Instruction::Add { rd, .. }
+            | Instruction::Sub { rd, .. }
+            | Instruction::Mul { rd, .. }
+            | Instruction::Div { rd, .. }
+            | Instruction::Rem { rd, .. }
+            | Instruction::Tcmp { rd, .. }
+            | Instruction::Shl { rd, .. }
+            | Instruction::Shr { rd, .. }
+            | Instruction::Addi { rd, .. }
+            | Instruction::Load { rd, .. }
+            | Instruction::Ldm { rd, .. } => Some(rd),
+            Instruction::Call { .. } | Instruction::Ret => Some(SP),
+            Instruction::Nop | Instruction::Halt | Instruction::Store { .. } | Instruction::Stm { .. }
+            | Instruction::Beq { .. } | Instruction::Bne { .. } | Instruction::Blt { .. }
+            | Instruction::Jmp { .. } | Instruction::Iret | Instruction::Custom { .. } => None,
+        }
+    }
+
+    /// The registers the instruction reads, in operand order. `call` and `ret` read `sp`, `stm`
+    /// reads its base then every register of its run. `Custom` instructions report none.
+    pub fn sources(&self) -> Vec<Register> {
+        match *self {
+            Instruction::Add { rs1, rs2, .. }
+            | Instruction::Sub { rs1, rs2, .. }
+            | Instruction::Mul { rs1, rs2, .. }
+            | Instruction::Div { rs1, rs2, .. }
+            | Instruction::Rem { rs1, rs2, .. }
+            | Instruction::Tcmp { rs1, rs2, .. }
+            | Instruction::Shl { rs1, rs2, .. }
+            | Instruction::Shr { rs1, rs2, .. }
+            | Instruction::Store { rs1, rs2, .. }
+            | Instruction::Beq { rs1, rs2, .. }
+            | Instruction::Bne { rs1, rs2, .. }
+            | Instruction::Blt { rs1, rs2, .. } => vec![rs1, rs2],
+            Instruction::Addi { rs1, .. }
+            | Instruction::Load { rs1, .. }
+            | Instruction::Ldm { rs1, .. } => vec![rs1],
+            Instruction::Stm { rs1, rs2, count } => {
+                std::iter::once(rs1).chain(register_run(rs2, count).into_iter().flatten()).collect()
+            }
+            Instruction::Call { .. } | Instruction::Ret => vec![SP],
+            Instruction::Nop | Instruction::Halt | Instruction::Jmp { .. } | Instruction::Iret
+            | Instruction::Custom { .. } => Vec::new(),
+        }
+    }
+}
+
+/// Decodes a word into an instruction.
+/// Trits outside the fields used by the opcode's format are ignored.
+/// R format words must hold a funct the opcode takes, even when it isn't shared.
+pub fn decode(word: Word) -> Result<Instruction, DecodeError> {
+    decode_width(word)
+}
+
+/// `decode` for a machine whose words are `N` trits wide. Immediates run to the top of the word,
+/// see `immediate12_field`. Fails to compile for words narrower than `MIN_WORD_TRITS`.
+pub fn decode_width<const N: usize>(word: BalancedInt<N>) -> Result<Instruction<N>, DecodeError>
+where
+    BalancedInt<N>: MachineWord,
+{
+    const { assert!(N >= MIN_WORD_TRITS, "an R format instruction needs MIN_WORD_TRITS trits") };
+    if word.opcode_trits().to_int() == ILLEGAL_OPCODE {
+        return Err(DecodeError::IllegalOpcode);
+    }
+    let opcode = OpCode::from_trits(word.opcode_trits())
+        .map_err(|err| DecodeError::InvalidOpcode(err.0.into()))?;
+    let funct = if opcode.format() == Format::R {
+        let value = FUNCT_FIELD.read(&word);
+        Funct::from_field(value)
+            .filter(|funct| opcode.functs().contains(funct))
+            .ok_or(DecodeError::InvalidFunct { opcode, funct: value })?
+    } else {
+        Funct::Base
+    };
+
+    let (rd, rs1, rs2) = (word.rd(), word.rs1(), word.rs2());
+    let kind = opcode.immediate_kind().unwrap_or_default();
+    let immediate = |field: Field| {
+        word.read_immediate_as(field.start, field.len, kind).expect("field lies inside a word")
+    };
+    let imm = immediate(immediate12_field(N));
+    // B format instructions keep their two sources in the rd and rs1 slots.
+    let (b_rs1, b_rs2) = (rd, rs1);
+
+    Ok(match opcode {
+        OpCode::NOP => Instruction::Nop,
+        OpCode::HALT => Instruction::Halt,
+        OpCode::ADD => Instruction::Add { rd, rs1, rs2 },
+        OpCode::SUB => Instruction::Sub { rd, rs1, rs2 },
+        OpCode::MUL => Instruction::Mul { rd, rs1, rs2 },
+        OpCode::DIV => Instruction::Div { rd, rs1, rs2 },
+        OpCode::REM => Instruction::Rem { rd, rs1, rs2 },
+        OpCode::TCMP => Instruction::Tcmp { rd, rs1, rs2 },
+        OpCode::SHIFT if funct == Funct::Left => Instruction::Shl { rd, rs1, rs2 },
+        OpCode::SHIFT => Instruction::Shr { rd, rs1, rs2 },
+        OpCode::ADDI => Instruction::Addi { rd, rs1, imm },
+        OpCode::LOAD => Instruction::Load { rd, rs1, imm },
+        OpCode::STORE => Instruction::Store { rs1: b_rs1, rs2: b_rs2, imm },
+        OpCode::LDM | OpCode::STM => {
+            let first = if opcode == OpCode::LDM { rd } else { b_rs2 };
+            if register_run(first, imm).is_none() {
+                return Err(DecodeError::InvalidRegisterRun { opcode, first, count: imm.0 });
+            }
+            if opcode == OpCode::LDM {
+                Instruction::Ldm { rd, rs1, count: imm }
+            } else {
+                Instruction::Stm { rs1: b_rs1, rs2: b_rs2, count: imm }
+            }
+        }
+        OpCode::BEQ => Instruction::Beq { rs1: b_rs1, rs2: b_rs2, imm },
+        OpCode::BNE => Instruction::Bne { rs1: b_rs1, rs2: b_rs2, imm },
+        OpCode::BLT => Instruction::Blt { rs1: b_rs1, rs2: b_rs2, imm },
+        OpCode::JMP => Instruction::Jmp { imm: immediate(immediate18_field(N)) },
+        OpCode::CALL => Instruction::Call { imm: immediate(immediate18_field(N)) },
+        OpCode::RET => Instruction::Ret,
+        OpCode::IRET => Instruction::Iret,
+    })
+}
+
+/// Decodes every word of a program, yielding each address with its result so words that don't
+/// decode, like data, show up in place without stopping the rest.
+pub fn decode_program(words: &[Word]) -> impl Iterator<Item = (usize, Result<Instruction, DecodeError>)> + '_ {
+    words.iter().enumerate().map(|(address, &word)| (address, decode(word)))
+}
+
+/// Encodes an instruction into a word, leaving unused trits zero.
+/// `Custom` instructions encode to their word unchanged.
+pub fn encode(instruction: &Instruction) -> Result<Word, ArchError> {
+    encode_width(instruction)
+}
+
+/// `encode` for a machine whose words are `N` trits wide, erroring if an immediate doesn't fit
+/// the narrower fields.
+pub fn encode_width<const N: usize>(instruction: &Instruction<N>) -> Result<BalancedInt<N>, ArchError>
+where
+    BalancedInt<N>: MachineWord,
+{
+    const { assert!(N >= MIN_WORD_TRITS, "an R format instruction needs MIN_WORD_TRITS trits") };
+    let opcode = match *instruction {
+        Instruction::Custom { word, .. } => return Ok(word),
+        _ => instruction.opcode().expect("every other instruction has an opcode"),
+    };
+    let mut word = BalancedInt::<N>::zero();
+    word.write_opcode(opcode)?;
+    let kind = opcode.immediate_kind().unwrap_or_default();
+    // The balanced value the field stores, errors still name the immediate as written.
+    let write_immediate = |word: &mut BalancedInt<N>, field: Field, imm: Immediate| {
+        field.write(word, kind.to_field(imm.0, field.len))
+            .map_err(|_| ArchError::ImmediateOutOfRange(imm.0))
+    };
+
+    match *instruction {
+        Instruction::Nop | Instruction::Halt | Instruction::Ret | Instruction::Iret
+        | Instruction::Custom { .. } => {}
+        Instruction::Add { rd, rs1, rs2 }
+        | Instruction::Sub { rd, rs1, rs2 }
+        | Instruction::Mul { rd, rs1, rs2 }
+        | Instruction::Div { rd, rs1, rs2 }
+        | Instruction::Rem { rd, rs1, rs2 }
+        | Instruction::Tcmp { rd, rs1, rs2 }
+        | Instruction::Shl { rd, rs1, rs2 }
+        | Instruction::Shr { rd, rs1, rs2 } => {
+            word.write_rd(rd)?.write_rs1(rs1)?.write_rs2(rs2)?;
+            FUNCT_FIELD.write(&mut word, instruction.funct() as i64)?;
+        }
+        Instruction::Addi { rd, rs1, imm }
+        | Instruction::Load { rd, rs1, imm }
+        | Instruction::Ldm { rd, rs1, count: imm } => {
+            word.write_rd(rd)?.write_rs1(rs1)?;
+            write_immediate(&mut word, immediate12_field(N), imm)?;
+        }
+        Instruction::Store { rs1, rs2, imm }
+        | Instruction::Stm { rs1, rs2, count: imm }
+        | Instruction::Beq { rs1, rs2, imm }
+        | Instruction::Bne { rs1, rs2, imm }
+        | Instruction::Blt { rs1, rs2, imm } => {
+            word.write_rd(rs1)?.write_rs1(rs2)?;
+            write_immediate(&mut word, immediate12_field(N), imm)?;
+        }
+        Instruction::Jmp { imm } | Instruction::Call { imm } => {
+            write_immediate(&mut word, immediate18_field(N), imm)?;
+        }
+    }
+    Ok(word)
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "r{}", self.0)
+    }
+}
+
+impl<const TRITS: usize> Display for Instruction<TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    /// Formats the instruction in the syntax accepted by the assembler.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = self.mnemonic();
+        match *self {
+            Instruction::Nop | Instruction::Halt | Instruction::Ret | Instruction::Iret => {
+                write!(f, "{op}")
+            }
+            Instruction::Custom { word, .. } => write!(f, "{op} {}", word.to_int()),
+            Instruction::Add { rd, rs1, rs2 }
+            | Instruction::Sub { rd, rs1, rs2 }
+            | Instruction::Mul { rd, rs1, rs2 }
+            | Instruction::Div { rd, rs1, rs2 }
+            | Instruction::Rem { rd, rs1, rs2 }
+            | Instruction::Tcmp { rd, rs1, rs2 }
+            | Instruction::Shl { rd, rs1, rs2 }
+            | Instruction::Shr { rd, rs1, rs2 } => write!(f, "{op} {rd}, {rs1}, {rs2}"),
+            Instruction::Addi { rd, rs1, imm }
+            | Instruction::Load { rd, rs1, imm }
+            | Instruction::Ldm { rd, rs1, count: imm } => write!(f, "{op} {rd}, {rs1}, {}", imm.0),
+            Instruction::Store { rs1, rs2, imm }
+            | Instruction::Stm { rs1, rs2, count: imm }
+            | Instruction::Beq { rs1, rs2, imm }
+            | Instruction::Bne { rs1, rs2, imm }
+            | Instruction::Blt { rs1, rs2, imm } => write!(f, "{op} {rs1}, {rs2}, {}", imm.0),
+            Instruction::Jmp { imm } | Instruction::Call { imm } => write!(f, "{op} {}", imm.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction_set::OPCODE_FIELD;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let instructions = [
+            Instruction::Nop,
+            Instruction::Halt,
+            Instruction::Add { rd: Register(1), rs1: Register(2), rs2: Register(26) },
+            Instruction::Div { rd: Register(14), rs1: Register(0), rs2: Register(13) },
+            Instruction::Tcmp { rd: Register(8), rs1: Register(9), rs2: Register(20) },
+            Instruction::Shl { rd: Register(1), rs1: Register(1), rs2: Register(15) },
+            Instruction::Shr { rd: Register(26), rs1: Register(3), rs2: Register(4) },
+            Instruction::Addi { rd: Register(3), rs1: Register(4), imm: Immediate(-5) },
+            Instruction::Load { rd: Register(3), rs1: Register(0), imm: Immediate(265_720) },
+            Instruction::Store { rs1: Register(5), rs2: Register(6), imm: Immediate(-265_720) },
+            Instruction::Ldm { rd: Register(24), rs1: Register(2), count: Immediate(3) },
+            Instruction::Stm { rs1: Register(2), rs2: Register(0), count: Immediate(27) },
+            Instruction::Blt { rs1: Register(7), rs2: Register(20), imm: Immediate(-3) },
+            Instruction::Jmp { imm: Immediate(-193_710_244) },
+            Instruction::Call { imm: Immediate(12) },
+            Instruction::Ret,
+            Instruction::Iret,
+        ];
+        for instruction in instructions {
+            let word = encode(&instruction).unwrap();
+            assert_eq!(decode(word), Ok(instruction), "round trip of {instruction}");
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_opcode() {
+        let mut word = Word::zero();
+        OPCODE_FIELD.write(&mut word, -7).unwrap();
+        assert_eq!(decode(word), Err(DecodeError::InvalidOpcode(-7)));
+    }
+
+    #[test]
+    fn test_zero_word_is_nop() {
+        assert_eq!(decode(Word::zero()), Ok(Instruction::Nop));
+        assert_eq!(encode(&Instruction::Nop).unwrap(), Word::zero());
+    }
+
+    #[test]
+    fn test_decode_illegal_opcode() {
+        let mut word = Word::zero();
+        OPCODE_FIELD.write(&mut word, ILLEGAL_OPCODE as i64).unwrap();
+        assert_eq!(word.to_int(), -364);
+        assert_eq!(decode(word), Err(DecodeError::IllegalOpcode));
+        // The other fields don't matter.
+        word.write_rd(Register(4)).unwrap();
+        assert_eq!(decode(word), Err(DecodeError::IllegalOpcode));
+    }
+
+    #[test]
+    fn test_shared_opcode_funct() {
+        let (rd, rs1, rs2) = (Register(1), Register(2), Register(3));
+        let shl = encode(&Instruction::Shl { rd, rs1, rs2 }).unwrap();
+        let shr = encode(&Instruction::Shr { rd, rs1, rs2 }).unwrap();
+        assert_eq!(shl.opcode().unwrap(), OpCode::SHIFT);
+        assert_eq!(shr.opcode().unwrap(), OpCode::SHIFT);
+        assert_eq!(FUNCT_FIELD.read(&shl), Funct::Left as i64);
+        assert_eq!(FUNCT_FIELD.read(&shr), Funct::Right as i64);
+        assert_eq!(decode(shl), Ok(Instruction::Shl { rd, rs1, rs2 }));
+        assert_eq!(decode(shr), Ok(Instruction::Shr { rd, rs1, rs2 }));
+        assert_eq!(decode(shr).unwrap().to_string(), "shr r1, r2, r3");
+    }
+
+    #[test]
+    fn test_decode_invalid_funct() {
+        let mut shift = encode(&Instruction::Shl { rd: Register(1), rs1: Register(2), rs2: Register(3) }).unwrap();
+        FUNCT_FIELD.write(&mut shift, 0).unwrap();
+        assert_eq!(decode(shift), Err(DecodeError::InvalidFunct { opcode: OpCode::SHIFT, funct: 0 }));
+        FUNCT_FIELD.write(&mut shift, 5).unwrap();
+        assert_eq!(decode(shift), Err(DecodeError::InvalidFunct { opcode: OpCode::SHIFT, funct: 5 }));
+
+        let mut add = encode(&Instruction::Add { rd: Register(1), rs1: Register(2), rs2: Register(3) }).unwrap();
+        FUNCT_FIELD.write(&mut add, 1).unwrap();
+        assert_eq!(decode(add), Err(DecodeError::InvalidFunct { opcode: OpCode::ADD, funct: 1 }));
+        // Outside R format the funct trits belong to the immediate.
+        let addi = encode(&Instruction::Addi { rd: Register(1), rs1: Register(2), imm: Immediate(-265_720) }).unwrap();
+        assert!(decode(addi).is_ok());
+    }
+
+    #[test]
+    fn test_decode_invalid_register_run() {
+        let mut ldm = encode(&Instruction::Ldm { rd: Register(20), rs1: Register(2), count: Immediate(7) }).unwrap();
+        assert!(decode(ldm).is_ok());
+        // Counts are unsigned, stored offset by (3^12 - 1) / 2.
+        assert_eq!(ldm.immediate12(), Immediate(7 - 265_720));
+        ldm.write_immediate12(Immediate(8 - 265_720)).unwrap();
+        assert_eq!(
+            decode(ldm),
+            Err(DecodeError::InvalidRegisterRun { opcode: OpCode::LDM, first: Register(20), count: 8 })
+        );
+        ldm.write_immediate12(Immediate(0)).unwrap();
+        assert_eq!(
+            decode(ldm),
+            Err(DecodeError::InvalidRegisterRun { opcode: OpCode::LDM, first: Register(20), count: 265_720 })
+        );
+        // stm counts from its second operand, the register in the rs1 slot.
+        let stm = encode(&Instruction::Stm { rs1: Register(26), rs2: Register(1), count: Immediate(27) }).unwrap();
+        assert_eq!(
+            decode(stm),
+            Err(DecodeError::InvalidRegisterRun { opcode: OpCode::STM, first: Register(1), count: 27 })
+        );
+        let negative: Instruction = Instruction::Stm { rs1: Register(26), rs2: Register(1), count: Immediate(-1) };
+        assert!(matches!(encode(&negative), Err(ArchError::ImmediateOutOfRange(-1))));
+        assert_eq!(register_run(Register(25), Immediate(2)).unwrap().collect::<Vec<_>>(), [Register(25), Register(26)]);
+        assert_eq!(register_run(Register(3), Immediate(0)).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_other_word_widths() {
+        fn addi<const N: usize>() -> Instruction<N> {
+            Instruction::Addi { rd: Register(4), rs1: Register(5), imm: Immediate(-364) }
+        }
+        let short = encode_width::<18>(&addi()).unwrap();
+        assert_eq!(decode_width(short), Ok(addi()));
+        // An 18 trit word leaves 6 trits for the immediate.
+        let wide = Instruction::Addi { rd: Register(4), rs1: Register(5), imm: Immediate(365) };
+        assert!(matches!(encode_width::<18>(&wide), Err(ArchError::ImmediateOutOfRange(365))));
+
+        let far = Instruction::Jmp { imm: Immediate(100_000_000_000) };
+        let long = encode_width::<36>(&far).unwrap();
+        assert_eq!(decode_width(long), Ok(far));
+        assert!(encode(&far.resize()).is_err());
+        assert_eq!(encode_width::<24>(&addi()).unwrap(), encode(&addi()).unwrap());
+
+        let custom = Instruction::Custom { opcode: 200, word: BalancedInt::<36>::from_int(3_i64.pow(34)) };
+        assert_eq!(encode_width(&custom).unwrap().to_int(), 3_i64.pow(34));
+    }
+
+    #[test]
+    fn test_decode_program() {
+        let halt = encode(&Instruction::Halt).unwrap();
+        let addi = Instruction::Addi { rd: Register(1), rs1: Register(0), imm: Immediate(4) };
+        let mut illegal = Word::zero();
+        OPCODE_FIELD.write(&mut illegal, -1).unwrap();
+        let words = [encode(&addi).unwrap(), illegal, halt];
+
+        let decoded: Vec<_> = decode_program(&words).collect();
+        assert_eq!(decoded, [
+            (0, Ok(addi)),
+            (1, Err(DecodeError::InvalidOpcode(-1))),
+            (2, Ok(Instruction::Halt)),
+        ]);
+        assert_eq!(decode_program(&[]).count(), 0);
+    }
+
+    #[test]
+    fn test_describe() {
+        let add: Instruction = Instruction::Add { rd: Register(1), rs1: Register(2), rs2: Register(3) };
+        assert_eq!(add.describe(), "r1 = r2 + r3");
+        let beq: Instruction = Instruction::Beq { rs1: Register(4), rs2: Register(0), imm: Immediate(-2) };
+        assert_eq!(beq.describe(), "pc += -2 if r4 == r0");
+        let store: Instruction = Instruction::Store { rs1: Register(0), rs2: Register(1), imm: Immediate(12) };
+        assert_eq!(store.describe(), "mem[r0 + 12] = r1");
+    }
+
+    #[test]
+    fn test_destination_and_sources() {
+        let add: Instruction = Instruction::Add { rd: Register(1), rs1: Register(2), rs2: Register(3) };
+        assert_eq!(add.destination(), Some(Register(1)));
+        assert_eq!(add.sources(), [Register(2), Register(3)]);
+        let beq: Instruction = Instruction::Beq { rs1: Register(4), rs2: Register(5), imm: Immediate(-2) };
+        assert_eq!(beq.destination(), None);
+        assert_eq!(beq.sources(), [Register(4), Register(5)]);
+
+        let stm: Instruction = Instruction::Stm { rs1: SP, rs2: Register(3), count: Immediate(2) };
+        assert_eq!(stm.sources(), [SP, Register(3), Register(4)]);
+        assert_eq!(Instruction::<24>::Ret.destination(), Some(SP));
+        assert!(Instruction::<24>::Jmp { imm: Immediate(4) }.sources().is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let addi: Instruction = Instruction::Addi { rd: Register(1), rs1: Register(2), imm: Immediate(-5) };
+        assert_eq!(addi.to_string(), "addi r1, r2, -5");
+        assert_eq!(Instruction::<24>::Jmp { imm: Immediate(4) }.to_string(), "jmp 4");
+    }
+}
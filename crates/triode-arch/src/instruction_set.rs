@@ -1,78 +1,369 @@
-use std::convert::Infallible;
-use bternary::Word;
-use crate::opcode::{InvalidOpCode, OpCode};
+use bternary::balanced_int::{balanced_range, ArithmeticTernaryInteger, BalancedInt, BIntError, TernaryIntegerRepr};
+use crate::ArchError;
+use crate::opcode::{OpCode, OPCODE_TRITS};
 
+/// A run of `len` trits of an instruction word starting at trit `start`, least significant first.
+/// Instruction formats are built from these instead of raw trit ranges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Field {
+    pub const fn new(start: usize, len: usize) -> Self {
+        Field { start, len }
+    }
+
+    /// Index of the field's most significant trit.
+    pub const fn end(&self) -> usize {
+        self.start + self.len - 1
+    }
+
+    /// Reads the field as a balanced value.
+    /// Panics if the field doesn't lie inside the word, which holds for every field defined here
+    /// on any `MachineWord`.
+    pub fn read<const N: usize>(&self, word: &BalancedInt<N>) -> i64
+    where
+        BalancedInt<N>: TernaryIntegerRepr<Int = i64>,
+    {
+        word.read_trit_range(self.start, self.end())
+            .expect("field lies inside a word")
+    }
+
+    /// Writes `value` into the field, leaving the rest of the word untouched.
+    /// Errors if the value doesn't fit in `len` trits.
+    pub fn write<const N: usize>(&self, word: &mut BalancedInt<N>, value: i64) -> Result<(), ArchError>
+    where
+        BalancedInt<N>: TernaryIntegerRepr<Int = i64>,
+    {
+        word.write_trit_range(value, self.start, self.end())?;
+        Ok(())
+    }
+}
+
+pub const OPCODE_FIELD: Field = Field::new(0, OPCODE_TRITS);
+pub const RD_FIELD: Field = Field::new(6, 3);
+pub const RS1_FIELD: Field = Field::new(9, 3);
+pub const RS2_FIELD: Field = Field::new(12, 3);
+/// Only R format words have a funct, see `Funct`.
+pub const FUNCT_FIELD: Field = Field::new(15, 3);
+pub const IMMEDIATE12_FIELD: Field = Field::new(12, 12);
+pub const IMMEDIATE18_FIELD: Field = Field::new(6, 18);
+
+/// The narrowest word the formats fit in: an R format word uses the low 18 trits.
+pub const MIN_WORD_TRITS: usize = 18;
+
+/// `IMMEDIATE12_FIELD` in a `trits` wide word. Immediates run to the top trit, so they only
+/// hold 12 trits on a Word.
+pub const fn immediate12_field(trits: usize) -> Field {
+    Field::new(IMMEDIATE12_FIELD.start, trits - IMMEDIATE12_FIELD.start)
+}
+
+/// `IMMEDIATE18_FIELD` in a `trits` wide word, see `immediate12_field`.
+pub const fn immediate18_field(trits: usize) -> Field {
+    Field::new(IMMEDIATE18_FIELD.start, trits - IMMEDIATE18_FIELD.start)
+}
+
+/// Number of general purpose registers, one for every value of a 3 trit field.
+pub const REGISTER_COUNT: usize = 27;
+
+/// Stack pointer by convention, used implicitly by `call` and `ret`.
+/// The stack grows down: a push decrements `sp` then stores at the new `sp`.
+pub const SP: Register = Register(2);
+
+/// A register index in `0..REGISTER_COUNT`.
+/// Register fields are balanced so indices above 13 are stored as `index - 27`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Register(pub u8);
 
+/// Calling convention names, indexed by register number.
+const ABI_NAMES: [&str; REGISTER_COUNT] = [
+    "zero", "fp", "sp",
+    "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7",
+    "t0", "t1", "t2", "t3", "t4", "t5", "t6", "t7",
+    "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7",
+];
+
+impl Register {
+    /// The calling convention name: `zero`, frame pointer `fp`, stack pointer `sp`,
+    /// arguments `a0`-`a7`, temporaries `t0`-`t7` and saved registers `s0`-`s7`.
+    pub fn abi_name(&self) -> Option<&'static str> {
+        ABI_NAMES.get(self.0 as usize).copied()
+    }
+
+    fn to_field(self) -> Result<i64, ArchError> {
+        match self.0 as usize {
+            i if i <= REGISTER_COUNT / 2 => Ok(i as i64),
+            i if i < REGISTER_COUNT => Ok(i as i64 - REGISTER_COUNT as i64),
+            _ => Err(ArchError::InvalidRegister(self.0)),
+        }
+    }
+
+    fn from_field(value: i64) -> Register {
+        Register(value.rem_euclid(REGISTER_COUNT as i64) as u8)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Immediate(pub i64);
 
+/// How the trits of an immediate field map to a number, see `OpCode::immediate_kind`.
+///
+/// Balanced ternary is signed by nature, so an unsigned immediate is stored offset: each trit
+/// reads as the digit `trit + 1`, which is the signed value plus `(3^n - 1) / 2` for an `n` trit
+/// field. All `T` reads as 0 and all `1` as `3^n - 1`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImmediateKind {
+    /// The balanced value, `-(3^n - 1) / 2..=(3^n - 1) / 2`.
+    #[default]
+    Signed,
+    /// The offset value, `0..=3^n - 1`.
+    Unsigned,
+}
+
+impl ImmediateKind {
+    /// The immediate an `n` trit field holding the balanced value `field` stands for.
+    pub fn from_field(self, field: i64, trits: usize) -> i64 {
+        match self {
+            ImmediateKind::Signed => field,
+            ImmediateKind::Unsigned => field.saturating_add(balanced_range(trits as u32).1),
+        }
+    }
+
+    /// The balanced value an `n` trit field stores `imm` as, undoing `from_field`.
+    /// An immediate outside the kind's range maps outside the field's range, so writing it fails.
+    pub fn to_field(self, imm: i64, trits: usize) -> i64 {
+        match self {
+            ImmediateKind::Signed => imm,
+            ImmediateKind::Unsigned => imm.saturating_sub(balanced_range(trits as u32).1),
+        }
+    }
+}
 
 pub trait InstructionSet {
-    fn opcode(&self) -> OpCode;
+    /// The raw opcode field, valid or not.
+    fn opcode_trits(&self) -> BalancedInt<OPCODE_TRITS>;
+    fn opcode(&self) -> Result<OpCode, ArchError>;
     fn rd(&self) -> Register;
     fn rs1(&self) -> Register;
     fn rs2(&self) -> Register;
+    /// The immediate from trit 12 to the top of the word, see `immediate12_field`.
     fn immediate12(&self) -> Immediate;
+    /// The immediate from trit 6 to the top of the word, see `immediate18_field`.
     fn immediate18(&self) -> Immediate;
+    /// The `trits` wide balanced immediate starting at trit `start`, any width the word holds.
+    /// Errors with `RangeInvalid` for an empty field or one running past the word.
+    fn read_immediate(&self, start: usize, trits: usize) -> Result<Immediate, BIntError>;
+    /// `read_immediate` interpreted as `kind`.
+    fn read_immediate_as(&self, start: usize, trits: usize, kind: ImmediateKind) -> Result<Immediate, BIntError>;
 
-    fn write_opcode(&mut self, opcode: OpCode) -> &mut Self;
-    fn write_rd(&mut self, rd: Register) -> &mut Self;
-    fn write_rs1(&mut self, rs1: Register) -> &mut Self;
-    fn write_rs2(&mut self, rs2: Register) -> &mut Self;
-    fn write_immediate12(&mut self, immediate12: Immediate) -> &mut Self;
-    fn write_immediate18(&mut self, immediate18: Immediate) -> &mut Self;
+    fn write_opcode(&mut self, opcode: OpCode) -> Result<&mut Self, ArchError>;
+    fn write_rd(&mut self, rd: Register) -> Result<&mut Self, ArchError>;
+    fn write_rs1(&mut self, rs1: Register) -> Result<&mut Self, ArchError>;
+    fn write_rs2(&mut self, rs2: Register) -> Result<&mut Self, ArchError>;
+    fn write_immediate12(&mut self, immediate12: Immediate) -> Result<&mut Self, ArchError>;
+    fn write_immediate18(&mut self, immediate18: Immediate) -> Result<&mut Self, ArchError>;
 }
 
-impl InstructionSet for Word {
-    fn opcode(&self) -> Result<OpCode, InvalidOpCode> {
-        let res = self.read_trit_range(0,6);
-        if let Err(err) = res {
-        }
+/// A word the ISA can be laid out in, from `MIN_WORD_TRITS` up to the 40 trits of an `i64`.
+/// `Word` is the Triode's own, other widths model machines built around a different word size.
+pub trait MachineWord: InstructionSet + TernaryIntegerRepr<Int = i64> + ArithmeticTernaryInteger {}
+
+impl<const N: usize> MachineWord for BalancedInt<N>
+where
+    BalancedInt<N>: TernaryIntegerRepr<Int = i64> + ArithmeticTernaryInteger,
+{}
+
+impl<const N: usize> InstructionSet for BalancedInt<N>
+where
+    BalancedInt<N>: TernaryIntegerRepr<Int = i64>,
+{
+    fn opcode_trits(&self) -> BalancedInt<OPCODE_TRITS> {
+        BalancedInt::new(std::array::from_fn(|i| self[OPCODE_FIELD.start + i]))
+    }
+
+    fn opcode(&self) -> Result<OpCode, ArchError> {
+        Ok(OpCode::from_trits(self.opcode_trits())?)
     }
 
     fn rd(&self) -> Register {
-        todo!()
+        Register::from_field(RD_FIELD.read(self))
     }
 
     fn rs1(&self) -> Register {
-        todo!()
+        Register::from_field(RS1_FIELD.read(self))
     }
 
     fn rs2(&self) -> Register {
-        todo!()
+        Register::from_field(RS2_FIELD.read(self))
     }
 
     fn immediate12(&self) -> Immediate {
-        todo!()
+        let field = immediate12_field(N);
+        self.read_immediate(field.start, field.len)
+            .expect("field lies inside a word")
     }
 
     fn immediate18(&self) -> Immediate {
-        todo!()
+        let field = immediate18_field(N);
+        self.read_immediate(field.start, field.len)
+            .expect("field lies inside a word")
+    }
+
+    fn read_immediate(&self, start: usize, trits: usize) -> Result<Immediate, BIntError> {
+        let end = (start + trits).checked_sub(1).ok_or(BIntError::RangeInvalid(start, start))?;
+        Ok(Immediate(self.read_trit_range(start, end)?))
+    }
+
+    fn read_immediate_as(&self, start: usize, trits: usize, kind: ImmediateKind) -> Result<Immediate, BIntError> {
+        Ok(Immediate(kind.from_field(self.read_immediate(start, trits)?.0, trits)))
+    }
+
+    fn write_opcode(&mut self, opcode: OpCode) -> Result<&mut Self, ArchError> {
+        for (i, &trit) in opcode.to_trits().iter().enumerate() {
+            self[OPCODE_FIELD.start + i] = trit;
+        }
+        Ok(self)
+    }
+
+    fn write_rd(&mut self, rd: Register) -> Result<&mut Self, ArchError> {
+        RD_FIELD.write(self, rd.to_field()?)?;
+        Ok(self)
+    }
+
+    fn write_rs1(&mut self, rs1: Register) -> Result<&mut Self, ArchError> {
+        RS1_FIELD.write(self, rs1.to_field()?)?;
+        Ok(self)
+    }
+
+    fn write_rs2(&mut self, rs2: Register) -> Result<&mut Self, ArchError> {
+        RS2_FIELD.write(self, rs2.to_field()?)?;
+        Ok(self)
+    }
+
+    fn write_immediate12(&mut self, immediate12: Immediate) -> Result<&mut Self, ArchError> {
+        immediate12_field(N).write(self, immediate12.0)
+            .map_err(|_| ArchError::ImmediateOutOfRange(immediate12.0))?;
+        Ok(self)
+    }
+
+    fn write_immediate18(&mut self, immediate18: Immediate) -> Result<&mut Self, ArchError> {
+        immediate18_field(N).write(self, immediate18.0)
+            .map_err(|_| ArchError::ImmediateOutOfRange(immediate18.0))?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bternary::Word;
+    use super::*;
+
+    #[test]
+    fn test_fields_round_trip() {
+        let mut word = Word::zero();
+        word.write_opcode(OpCode::ADDI).unwrap()
+            .write_rd(Register(26)).unwrap()
+            .write_rs1(Register(13)).unwrap()
+            .write_immediate12(Immediate(-265_720)).unwrap();
+
+        assert_eq!(word.opcode().unwrap(), OpCode::ADDI);
+        assert_eq!(word.rd(), Register(26));
+        assert_eq!(word.rs1(), Register(13));
+        assert_eq!(word.immediate12(), Immediate(-265_720));
     }
 
-    fn write_opcode(&mut self, opcode: OpCode) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_read_immediate() {
+        let mut word = Word::zero();
+        word.write_opcode(OpCode::ADDI).unwrap().write_immediate12(Immediate(-1234)).unwrap();
+        assert_eq!(word.read_immediate(12, 12), Ok(Immediate(-1234)));
+        assert_eq!(word.read_immediate(12, 12), Ok(word.immediate12()));
+
+        word.write_immediate18(Immediate(-193_710_244)).unwrap();
+        assert_eq!(word.read_immediate(6, 18), Ok(Immediate(-193_710_244)));
+        word.write_immediate18(Immediate(77)).unwrap();
+        assert_eq!(word.read_immediate(6, 18), Ok(word.immediate18()));
+        // 77 is 10T0T, so any window of at least 5 trits from the bottom reads the same value.
+        assert_eq!(word.read_immediate(6, 9), Ok(Immediate(77)));
+
+        assert_eq!(word.read_immediate(20, 5), Err(BIntError::RangeInvalid(20, 24)));
+        assert_eq!(word.read_immediate(0, 0), Err(BIntError::RangeInvalid(0, 0)));
     }
 
-    fn write_rd(&mut self, rd: Register) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_immediate_kinds() {
+        let mut word = Word::zero();
+        word.write_immediate12(Immediate(-1234)).unwrap();
+        let signed = word.read_immediate_as(12, 12, ImmediateKind::Signed).unwrap();
+        let unsigned = word.read_immediate_as(12, 12, ImmediateKind::Unsigned).unwrap();
+        assert_eq!(signed, Immediate(-1234));
+        // Offset by (3^12 - 1) / 2 = 265_720.
+        assert_eq!(unsigned, Immediate(264_486));
+        assert_eq!(ImmediateKind::Unsigned.to_field(unsigned.0, 12), -1234);
+
+        word.write_immediate12(Immediate(-265_720)).unwrap();
+        assert_eq!(word.read_immediate_as(12, 12, ImmediateKind::Unsigned), Ok(Immediate(0)));
+        word.write_immediate12(Immediate(265_720)).unwrap();
+        assert_eq!(word.read_immediate_as(12, 12, ImmediateKind::Unsigned), Ok(Immediate(531_440)));
+        assert_eq!(ImmediateKind::Unsigned.to_field(-1, 12), -265_721);
+        assert_eq!(OpCode::BEQ.immediate_kind(), Some(ImmediateKind::Signed));
+        assert_eq!(OpCode::LDM.immediate_kind(), Some(ImmediateKind::Unsigned));
+        assert_eq!(OpCode::STM.immediate_kind(), Some(ImmediateKind::Unsigned));
+        assert_eq!(OpCode::ADD.immediate_kind(), None);
     }
 
-    fn write_rs1(&mut self, rs1: Register) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_r_type_layout_round_trip() {
+        let layout = [OPCODE_FIELD, RD_FIELD, RS1_FIELD, RS2_FIELD, FUNCT_FIELD];
+        let values = [OpCode::ADD as i64, 13, -13, 7, -1];
+        // The fields tile the low 18 trits without gaps or overlap.
+        for pair in layout.windows(2) {
+            assert_eq!(pair[0].end() + 1, pair[1].start);
+        }
+
+        let mut word = Word::zero();
+        for (field, value) in layout.iter().zip(values) {
+            field.write(&mut word, value).unwrap();
+        }
+        for (field, value) in layout.iter().zip(values) {
+            assert_eq!(field.read(&word), value);
+        }
+        assert_eq!(word.opcode().unwrap(), OpCode::ADD);
+        assert!(matches!(RD_FIELD.write(&mut word, 14), Err(ArchError::Field(_))));
+        assert_eq!(RD_FIELD.read(&word), 13);
     }
 
-    fn write_rs2(&mut self, rs2: Register) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_abi_names() {
+        assert_eq!(Register(0).abi_name(), Some("zero"));
+        assert_eq!(SP.abi_name(), Some("sp"));
+        assert_eq!(Register(3).abi_name(), Some("a0"));
+        assert_eq!(Register(26).abi_name(), Some("s7"));
+        assert_eq!(Register(27).abi_name(), None);
     }
 
-    fn write_immediate12(&mut self, immediate12: Immediate) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_write_errors() {
+        let mut word = Word::zero();
+        assert!(matches!(word.write_rd(Register(27)), Err(ArchError::InvalidRegister(27))));
+        assert!(matches!(
+            word.write_immediate12(Immediate(265_721)),
+            Err(ArchError::ImmediateOutOfRange(265_721))
+        ));
+        assert!(word.write_immediate18(Immediate(193_710_244)).is_ok());
+        assert!(word.write_immediate18(Immediate(193_710_245)).is_err());
     }
 
-    fn write_immediate18(&mut self, immediate18: Immediate) -> &mut Self {
-        todo!()
+    #[test]
+    fn test_invalid_opcode() {
+        let mut word = Word::zero();
+        OPCODE_FIELD.write(&mut word, -1).unwrap();
+        assert!(matches!(word.opcode(), Err(ArchError::InvalidOpcode(-1))));
+        OPCODE_FIELD.write(&mut word, 300).unwrap();
+        assert!(matches!(word.opcode(), Err(ArchError::InvalidOpcode(300))));
+        OPCODE_FIELD.write(&mut word, 200).unwrap();
+        assert!(matches!(word.opcode(), Err(ArchError::InvalidOpcode(200))));
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,26 @@
 pub mod opcode;
 pub mod instruction_set;
+pub mod instruction;
+pub mod fuzz;
+pub mod image;
 
+use bternary::balanced_int::BIntError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ArchError {
-    #[error("invalid opcode: 0x{0:02X}")]
-    InvalidOpcode(u8),
+    #[error("invalid opcode: {0}")]
+    InvalidOpcode(i16),
 
     #[error("value is out of range")]
     ValueOutOfRange(#[from] std::num::TryFromIntError),
-}
\ No newline at end of file
+
+    #[error("invalid register: r{0}")]
+    InvalidRegister(u8),
+
+    #[error("immediate {0} does not fit in its field")]
+    ImmediateOutOfRange(i64),
+
+    #[error(transparent)]
+    Field(#[from] BIntError),
+}
@@ -1,28 +1,318 @@
 use std::fmt::{Display, Formatter};
+use bternary::balanced_int::BalancedInt;
+use crate::instruction_set::ImmediateKind;
+
+/// Width of the opcode field at the bottom of every instruction word.
+pub const OPCODE_TRITS: usize = 6;
+
+/// An opcode field value reserved to never be an instruction, every trit `T`. Words holding it
+/// always fail to decode, so it marks a deliberate trap apart from opcodes not yet assigned.
+pub const ILLEGAL_OPCODE: i16 = -364;
+
+/// How the trits after the opcode are laid out for an instruction.
+/// See `instruction_set` for the exact trit ranges of each field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Opcode only, every other trit is ignored.
+    None,
+    /// `rd`, `rs1`, `rs2` and a 3 trit funct, see `Funct`.
+    R,
+    /// `rd`, `rs1` and a 12 trit immediate.
+    I,
+    /// Two source registers (stored in the `rd` and `rs1` slots) and a 12 trit immediate.
+    B,
+    /// An 18 trit immediate.
+    J,
+}
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum OpCode {
     NOP = 0x00,
+    HALT = 0x01,
+    ADD = 0x02,
+    SUB = 0x03,
+    MUL = 0x04,
+    DIV = 0x05,
+    REM = 0x06,
+    ADDI = 0x07,
+    LOAD = 0x08,
+    STORE = 0x09,
+    BEQ = 0x0A,
+    BNE = 0x0B,
+    BLT = 0x0C,
+    JMP = 0x0D,
+    CALL = 0x0E,
+    RET = 0x0F,
+    IRET = 0x10,
+    TCMP = 0x11,
+    /// `shl` and `shr`, told apart by their funct.
+    SHIFT = 0x12,
+    LDM = 0x13,
+    STM = 0x14,
 }
 
-#[derive(Debug)]
-pub struct InvalidOpCode(u8);
+impl OpCode {
+    pub const ALL: [OpCode; 21] = [
+        OpCode::NOP,
+        OpCode::HALT,
+        OpCode::ADD,
+        OpCode::SUB,
+        OpCode::MUL,
+        OpCode::DIV,
+        OpCode::REM,
+        OpCode::ADDI,
+        OpCode::LOAD,
+        OpCode::STORE,
+        OpCode::BEQ,
+        OpCode::BNE,
+        OpCode::BLT,
+        OpCode::JMP,
+        OpCode::CALL,
+        OpCode::RET,
+        OpCode::IRET,
+        OpCode::TCMP,
+        OpCode::SHIFT,
+        OpCode::LDM,
+        OpCode::STM,
+    ];
+
+    pub fn format(&self) -> Format {
+        match self {
+            OpCode::NOP | OpCode::HALT | OpCode::RET | OpCode::IRET => Format::None,
+            OpCode::ADD | OpCode::SUB | OpCode::MUL | OpCode::DIV | OpCode::REM | OpCode::TCMP
+            | OpCode::SHIFT => Format::R,
+            OpCode::ADDI | OpCode::LOAD | OpCode::LDM => Format::I,
+            OpCode::STORE | OpCode::STM | OpCode::BEQ | OpCode::BNE | OpCode::BLT => Format::B,
+            OpCode::JMP | OpCode::CALL => Format::J,
+        }
+    }
+
+    /// How `decode` and `encode` interpret the opcode's immediate, `None` if its format has none.
+    /// Offsets and displacements go either way and are signed, `ldm` and `stm` counts are
+    /// unsigned.
+    pub fn immediate_kind(&self) -> Option<ImmediateKind> {
+        match (self, self.format()) {
+            (_, Format::None | Format::R) => None,
+            (OpCode::LDM | OpCode::STM, _) => Some(ImmediateKind::Unsigned),
+            _ => Some(ImmediateKind::Signed),
+        }
+    }
+
+    /// The assembler mnemonic for this opcode.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            OpCode::NOP => "nop",
+            OpCode::HALT => "halt",
+            OpCode::ADD => "add",
+            OpCode::SUB => "sub",
+            OpCode::MUL => "mul",
+            OpCode::DIV => "div",
+            OpCode::REM => "rem",
+            OpCode::ADDI => "addi",
+            OpCode::LOAD => "load",
+            OpCode::STORE => "store",
+            OpCode::BEQ => "beq",
+            OpCode::BNE => "bne",
+            OpCode::BLT => "blt",
+            OpCode::JMP => "jmp",
+            OpCode::CALL => "call",
+            OpCode::RET => "ret",
+            OpCode::IRET => "iret",
+            OpCode::TCMP => "tcmp",
+            OpCode::SHIFT => "shift",
+            OpCode::LDM => "ldm",
+            OpCode::STM => "stm",
+        }
+    }
+
+    /// Cycles the emulator charges for executing this opcode, used for cycle accounting.
+    ///
+    /// | opcode                        | cycles |
+    /// |-------------------------------|--------|
+    /// | `nop`, `halt`                 | 1      |
+    /// | `add`, `sub`, `addi`, `tcmp`  | 1      |
+    /// | `shl`, `shr`                  | 1      |
+    /// | `mul`                         | 3      |
+    /// | `div`, `rem`                  | 8      |
+    /// | `load`, `store`               | 2      |
+    /// | `ldm`, `stm`                  | 2      |
+    /// | `beq`, `bne`, `blt`, `jmp`    | 1      |
+    /// | `call`, `ret`, `iret`         | 2      |
+    ///
+    /// Branches cost the same whether or not they are taken. `call` and `ret`
+    /// include the stack access. `ldm` and `stm` cost the same whatever their count.
+    pub fn base_cycles(&self) -> u32 {
+        match self {
+            OpCode::NOP | OpCode::HALT => 1,
+            OpCode::ADD | OpCode::SUB | OpCode::ADDI | OpCode::TCMP => 1,
+            OpCode::SHIFT => 1,
+            OpCode::MUL => 3,
+            OpCode::DIV | OpCode::REM => 8,
+            OpCode::LOAD | OpCode::STORE | OpCode::LDM | OpCode::STM => 2,
+            OpCode::BEQ | OpCode::BNE | OpCode::BLT | OpCode::JMP => 1,
+            OpCode::CALL | OpCode::RET | OpCode::IRET => 2,
+        }
+    }
+
+    /// The opcode as it is stored in the opcode field of an instruction word.
+    pub fn to_trits(&self) -> BalancedInt<OPCODE_TRITS> {
+        BalancedInt::from_int(*self as u8 as i16)
+    }
+
+    /// Reads the opcode field of an instruction word.
+    pub fn from_trits(trits: BalancedInt<OPCODE_TRITS>) -> Result<OpCode, InvalidOpCode> {
+        let value = trits.to_int();
+        OpCode::ALL
+            .into_iter()
+            .find(|op| *op as u8 as i16 == value)
+            .ok_or(InvalidOpCode(value))
+    }
+
+    /// The funct values this opcode accepts. An opcode shared by several instructions lists one
+    /// funct per instruction, every other opcode only takes `Funct::Base`.
+    pub fn functs(&self) -> &'static [Funct] {
+        match self {
+            OpCode::SHIFT => &[Funct::Left, Funct::Right],
+            _ => &[Funct::Base],
+        }
+    }
+
+    /// Looks up an opcode by its assembler mnemonic, ignoring case.
+    pub fn from_mnemonic(mnemonic: &str) -> Option<OpCode> {
+        OpCode::ALL
+            .into_iter()
+            .find(|op| op.mnemonic().eq_ignore_ascii_case(mnemonic))
+    }
+}
+
+impl Display for OpCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.mnemonic())
+    }
+}
+
+/// The funct field of R format words, selecting between instructions that share an opcode,
+/// like RISC-V's funct3. Values not listed here are reserved and fail to decode.
+#[repr(i8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Funct {
+    /// The only instruction of an opcode that isn't shared.
+    Base = 0,
+    /// `shl`, shifting toward the most significant trit.
+    Left = 1,
+    /// `shr`, shifting toward the least significant trit.
+    Right = -1,
+}
+
+impl Funct {
+    pub const ALL: [Funct; 3] = [Funct::Base, Funct::Left, Funct::Right];
+
+    /// The funct named by a funct field value, `None` if it is reserved.
+    pub fn from_field(value: i64) -> Option<Funct> {
+        Funct::ALL.into_iter().find(|&funct| funct as i64 == value)
+    }
+}
+
+/// The assembler mnemonic of the instruction with this opcode and funct.
+/// Only instructions sharing an opcode differ from `OpCode::mnemonic`.
+pub fn instruction_mnemonic(opcode: OpCode, funct: Funct) -> &'static str {
+    match (opcode, funct) {
+        (OpCode::SHIFT, Funct::Left) => "shl",
+        (OpCode::SHIFT, Funct::Right) => "shr",
+        _ => opcode.mnemonic(),
+    }
+}
+
+/// Looks up the opcode and funct of an instruction mnemonic, ignoring case.
+/// Unlike `OpCode::from_mnemonic` this never returns a shared opcode on its own, `shift` isn't
+/// an instruction while `shl` and `shr` are.
+pub fn lookup_mnemonic(mnemonic: &str) -> Option<(OpCode, Funct)> {
+    OpCode::ALL
+        .into_iter()
+        .flat_map(|op| op.functs().iter().map(move |&funct| (op, funct)))
+        .find(|&(op, funct)| instruction_mnemonic(op, funct).eq_ignore_ascii_case(mnemonic))
+}
+
+/// An opcode value, as read from the opcode field, that names no instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidOpCode(pub i16);
 
 impl Display for InvalidOpCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Invalid OpCode provided: {:x}", self.0))
+        f.write_fmt(format_args!("Invalid OpCode provided: {}", self.0))
     }
 }
 
 impl std::error::Error for InvalidOpCode {}
 
+impl From<InvalidOpCode> for crate::ArchError {
+    fn from(err: InvalidOpCode) -> Self {
+        crate::ArchError::InvalidOpcode(err.0)
+    }
+}
+
 impl TryFrom<u8> for OpCode {
     type Error = InvalidOpCode;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            0x00 => Ok(OpCode::NOP),
-            _ => Err(InvalidOpCode(value))
+        OpCode::ALL
+            .into_iter()
+            .find(|op| *op as u8 == value)
+            .ok_or(InvalidOpCode(value.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_u8_round_trip() {
+        for op in OpCode::ALL {
+            assert_eq!(OpCode::try_from(op as u8).unwrap(), op);
+        }
+        assert!(OpCode::try_from(0xFF).is_err());
+    }
+
+    #[test]
+    fn test_trits_round_trip() {
+        for op in OpCode::ALL {
+            assert_eq!(op.to_trits().to_int(), op as u8 as i16);
+            assert_eq!(OpCode::from_trits(op.to_trits()), Ok(op));
         }
+        assert_eq!(OpCode::from_trits(BalancedInt::from_int(-1)), Err(InvalidOpCode(-1)));
+        assert_eq!(OpCode::from_trits(BalancedInt::from_int(300)), Err(InvalidOpCode(300)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_mnemonic_round_trip() {
+        for op in OpCode::ALL {
+            assert_eq!(OpCode::from_mnemonic(op.mnemonic()), Some(op));
+        }
+        assert_eq!(OpCode::from_mnemonic("ADDI"), Some(OpCode::ADDI));
+        assert_eq!(OpCode::from_mnemonic("frobnicate"), None);
+    }
+
+    #[test]
+    fn test_lookup_mnemonic() {
+        assert_eq!(lookup_mnemonic("add"), Some((OpCode::ADD, Funct::Base)));
+        assert_eq!(lookup_mnemonic("SHL"), Some((OpCode::SHIFT, Funct::Left)));
+        assert_eq!(lookup_mnemonic("shr"), Some((OpCode::SHIFT, Funct::Right)));
+        assert_eq!(lookup_mnemonic("shift"), None);
+        for op in OpCode::ALL {
+            for &funct in op.functs() {
+                assert_eq!(lookup_mnemonic(instruction_mnemonic(op, funct)), Some((op, funct)));
+            }
+        }
+        assert_eq!(Funct::from_field(-1), Some(Funct::Right));
+        assert_eq!(Funct::from_field(2), None);
+    }
+
+    #[test]
+    fn test_base_cycles() {
+        assert!(OpCode::ALL.iter().all(|op| op.base_cycles() >= 1));
+        assert!(OpCode::MUL.base_cycles() > OpCode::ADD.base_cycles());
+        assert!(OpCode::DIV.base_cycles() > OpCode::MUL.base_cycles());
+    }
+}
@@ -0,0 +1,89 @@
+use bternary::Word;
+use triode_arch::instruction::decode_program;
+use triode_arch::instruction_set::{IMMEDIATE12_FIELD, IMMEDIATE18_FIELD};
+use triode_arch::opcode::Format;
+
+/// Column the optional `;` annotation starts at.
+const COMMENT_COLUMN: usize = 24;
+
+/// Controls what `disassemble` prints alongside each instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisasmOptions {
+    /// Prefix each line with its word address.
+    pub show_addresses: bool,
+    /// Append the raw trits of the immediate field as a comment, e.g. `; imm=00000000000T`.
+    pub show_immediate_trits: bool,
+}
+
+/// Disassembles a program, one line per word.
+/// Immediates are printed as signed decimal so the output reassembles to the same words.
+/// Words that don't decode are printed as `.word` data.
+pub fn disassemble(words: &[Word], options: &DisasmOptions) -> String {
+    let mut out = String::new();
+    for (address, decoded) in decode_program(words) {
+        if options.show_addresses {
+            out.push_str(&format!("{address:04}: "));
+        }
+
+        let word = words[address];
+        match decoded {
+            Ok(instruction) => {
+                let text = instruction.to_string();
+                let field = match instruction.opcode().map_or(Format::None, |op| op.format()) {
+                    Format::I | Format::B => Some(IMMEDIATE12_FIELD),
+                    Format::J => Some(IMMEDIATE18_FIELD),
+                    Format::None | Format::R => None,
+                };
+                match field {
+                    Some(field) if options.show_immediate_trits => {
+                        let trits: String = (field.start..=field.end()).rev()
+                            .map(|i| word[i].to_string())
+                            .collect();
+                        out.push_str(&format!("{text:<COMMENT_COLUMN$}; imm={trits}"));
+                    }
+                    _ => out.push_str(&text),
+                }
+            }
+            Err(_) => out.push_str(&format!(".word {}", word.to_int())),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assemble;
+
+    #[test]
+    fn test_negative_immediate_in_decimal() {
+        let words = assemble("addi r1, r2, -5").unwrap();
+        assert_eq!(disassemble(&words, &DisasmOptions::default()), "addi r1, r2, -5\n");
+
+        let options = DisasmOptions { show_immediate_trits: true, ..DisasmOptions::default() };
+        assert_eq!(
+            disassemble(&words, &options),
+            "addi r1, r2, -5         ; imm=000000000T11\n"
+        );
+    }
+
+    #[test]
+    fn test_addresses_and_data() {
+        let words = assemble("jmp -2\nhalt\n.word -1").unwrap();
+        let options = DisasmOptions { show_addresses: true, show_immediate_trits: true };
+        assert_eq!(
+            disassemble(&words, &options),
+            "0000: jmp -2                  ; imm=0000000000000000T1\n0001: halt\n0002: .word -1\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip_through_assembler() {
+        let source = "add r1, r2, r3\nstore r2, r1, -40\ncall 7\nret\nhalt\n";
+        let words = assemble(source).unwrap();
+        let text = disassemble(&words, &DisasmOptions::default());
+        assert_eq!(text, source);
+        assert_eq!(assemble(&text).unwrap(), words);
+    }
+}
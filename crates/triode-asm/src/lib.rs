@@ -0,0 +1,390 @@
+//! A line based assembler for the Triode instruction set.
+//!
+//! Each line holds at most one statement, anything after a `;` is a comment:
+//!
+//! ```text
+//! addi r1, r0, 5      ; instruction: mnemonic then comma separated operands
+//! .word 42, 0t1T0     ; one data word per value
+//! .tryte -5           ; a value range checked to a tryte, stored in its own word
+//! ```
+//!
+//! Registers are written `r0` to `r26`. Immediates and data values are either decimal
+//! (`-5`) or trit literals with a `0t` prefix, most significant trit first (`0t1T0` is 6).
+//! Data is emitted in place, so a `.word` table after the code lands right after it in memory.
+//!
+//! A line may start with a label, `loop:`, naming the address of the statement that follows it
+//! (or of the next statement if the line is otherwise empty). Instruction immediates can name a
+//! label instead of a value: branches, `jmp` and `call` get the offset from the instruction to
+//! the label, every other instruction gets the label's address.
+//!
+//! ```text
+//! loop:   addi r1, r1, -1
+//!         bne r1, r0, loop    ; assembles to an offset of -1
+//! ```
+
+pub mod disasm;
+
+pub use disasm::{disassemble, DisasmOptions};
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use bternary::Word;
+use bternary::balanced_int::{BalancedInt, TernaryIntegerRepr};
+use thiserror::Error;
+use triode_arch::ArchError;
+use triode_arch::instruction::{encode, Instruction};
+use triode_arch::instruction_set::{Immediate, Register, REGISTER_COUNT};
+use triode_arch::opcode::{lookup_mnemonic, Format, Funct, OpCode};
+
+#[derive(Error, Debug)]
+pub enum AsmError {
+    #[error("line {line}: unknown mnemonic `{mnemonic}`")]
+    UnknownMnemonic { line: usize, mnemonic: String },
+
+    #[error("line {line}: unknown directive `{directive}`")]
+    UnknownDirective { line: usize, directive: String },
+
+    #[error("line {line}: expected {expected} operands, found {found}")]
+    OperandCount { line: usize, expected: usize, found: usize },
+
+    #[error("line {line}: invalid register `{operand}`")]
+    InvalidRegister { line: usize, operand: String },
+
+    #[error("line {line}: invalid value `{operand}`")]
+    InvalidValue { line: usize, operand: String },
+
+    #[error("line {line}: {source}")]
+    Encode { line: usize, source: ArchError },
+
+    #[error("line {line}: undefined label `{label}`")]
+    UndefinedLabel { line: usize, label: String },
+
+    #[error("line {line}: label `{label}` is already defined")]
+    DuplicateLabel { line: usize, label: String },
+}
+
+/// One instruction or directive, with the address of the first word it assembles to.
+struct Statement<'a> {
+    line: usize,
+    address: usize,
+    head: &'a str,
+    operands: Vec<&'a str>,
+}
+
+/// Label addresses found by the first pass.
+type Labels<'a> = HashMap<&'a str, usize>;
+
+/// Assembles a program into the words that make up its memory image.
+/// The first pass collects statements and label addresses, the second encodes them.
+pub fn assemble(source: &str) -> Result<Vec<Word>, AsmError> {
+    let mut statements = Vec::new();
+    let mut labels = Labels::new();
+    let mut address = 0;
+
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let mut text = raw.split(';').next().unwrap_or("").trim();
+
+        if let Some((label, rest)) = text.split_once(':').filter(|(label, _)| is_label(label)) {
+            if labels.insert(label, address).is_some() {
+                return Err(AsmError::DuplicateLabel { line, label: label.to_string() });
+            }
+            text = rest.trim();
+        }
+        if text.is_empty() {
+            continue;
+        }
+
+        let (head, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let operands: Vec<&str> = if rest.trim().is_empty() {
+            Vec::new()
+        } else {
+            rest.split(',').map(str::trim).collect()
+        };
+
+        let statement = Statement { line, address, head, operands };
+        // Every directive emits one word per operand.
+        address += if head.starts_with('.') { statement.operands.len() } else { 1 };
+        statements.push(statement);
+    }
+
+    let mut words = Vec::with_capacity(address);
+    for statement in &statements {
+        if let Some(directive) = statement.head.strip_prefix('.') {
+            assemble_directive(directive, &statement.operands, statement.line, &mut words)?;
+        } else {
+            words.push(assemble_instruction(statement, &labels)?);
+        }
+    }
+
+    Ok(words)
+}
+
+/// Labels are an identifier: a letter or `_` followed by letters, digits or `_`.
+fn is_label(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn assemble_directive(directive: &str, operands: &[&str], line: usize, words: &mut Vec<Word>) -> Result<(), AsmError> {
+    if operands.is_empty() {
+        return Err(AsmError::OperandCount { line, expected: 1, found: 0 });
+    }
+
+    for operand in operands {
+        let word = match directive {
+            "word" => parse_value::<24>(operand, line)?,
+            "tryte" => {
+                let tryte = parse_value::<6>(operand, line)?;
+                Word::from_int(tryte.to_int() as i64)
+            }
+            _ => return Err(AsmError::UnknownDirective { line, directive: directive.to_string() }),
+        };
+        words.push(word);
+    }
+    Ok(())
+}
+
+/// Builds an `Instruction` from a mnemonic and operand strings, for tools that have those apart
+/// rather than as a line of assembly. An extension trait since `Instruction` lives in
+/// `triode_arch`, which knows nothing of assembler errors.
+pub trait ParseInstruction: Sized {
+    /// Parses one instruction, reading the mnemonic and operands the way `assemble` does.
+    /// There are no labels outside a program, so naming one is an `UndefinedLabel` error.
+    /// Errors report line 0.
+    fn parse(mnemonic: &str, operands: &[&str]) -> Result<Self, AsmError>;
+}
+
+impl ParseInstruction for Instruction {
+    fn parse(mnemonic: &str, operands: &[&str]) -> Result<Self, AsmError> {
+        let statement = Statement { line: 0, address: 0, head: mnemonic, operands: operands.to_vec() };
+        parse_instruction(&statement, &Labels::new())
+    }
+}
+
+fn assemble_instruction(statement: &Statement, labels: &Labels) -> Result<Word, AsmError> {
+    let instruction = parse_instruction(statement, labels)?;
+    encode(&instruction).map_err(|source| AsmError::Encode { line: statement.line, source })
+}
+
+/// The instruction a statement names. Operands are written in the order `Display` prints them,
+/// so B format sources come first even though they are stored in the rd and rs1 slots.
+fn parse_instruction(statement: &Statement, labels: &Labels) -> Result<Instruction, AsmError> {
+    let Statement { line, address, head: mnemonic, ref operands } = *statement;
+    let (opcode, funct) = lookup_mnemonic(mnemonic)
+        .ok_or_else(|| AsmError::UnknownMnemonic { line, mnemonic: mnemonic.to_string() })?;
+
+    let expected = match opcode.format() {
+        Format::None => 0,
+        Format::J => 1,
+        Format::R | Format::I | Format::B => 3,
+    };
+    if operands.len() != expected {
+        return Err(AsmError::OperandCount { line, expected, found: operands.len() });
+    }
+
+    let pc_relative = matches!(
+        opcode,
+        OpCode::BEQ | OpCode::BNE | OpCode::BLT | OpCode::JMP | OpCode::CALL
+    );
+    let immediate = |operand: &str| -> Result<Immediate, AsmError> {
+        if !is_label(operand) {
+            return parse_immediate(operand, line);
+        }
+        let target = *labels.get(operand)
+            .ok_or_else(|| AsmError::UndefinedLabel { line, label: operand.to_string() })?;
+        let value = if pc_relative { target as i64 - address as i64 } else { target as i64 };
+        Ok(Immediate(value))
+    };
+    let register = |index: usize| parse_register(operands[index], line);
+
+    Ok(match opcode.format() {
+        Format::None => match opcode {
+            OpCode::NOP => Instruction::Nop,
+            OpCode::HALT => Instruction::Halt,
+            OpCode::RET => Instruction::Ret,
+            _ => Instruction::Iret,
+        },
+        Format::R => {
+            let (rd, rs1, rs2) = (register(0)?, register(1)?, register(2)?);
+            match opcode {
+                OpCode::ADD => Instruction::Add { rd, rs1, rs2 },
+                OpCode::SUB => Instruction::Sub { rd, rs1, rs2 },
+                OpCode::MUL => Instruction::Mul { rd, rs1, rs2 },
+                OpCode::DIV => Instruction::Div { rd, rs1, rs2 },
+                OpCode::REM => Instruction::Rem { rd, rs1, rs2 },
+                OpCode::TCMP => Instruction::Tcmp { rd, rs1, rs2 },
+                _ if funct == Funct::Left => Instruction::Shl { rd, rs1, rs2 },
+                _ => Instruction::Shr { rd, rs1, rs2 },
+            }
+        }
+        Format::I => {
+            let (rd, rs1, imm) = (register(0)?, register(1)?, immediate(operands[2])?);
+            match opcode {
+                OpCode::ADDI => Instruction::Addi { rd, rs1, imm },
+                OpCode::LOAD => Instruction::Load { rd, rs1, imm },
+                _ => Instruction::Ldm { rd, rs1, count: imm },
+            }
+        }
+        Format::B => {
+            let (rs1, rs2, imm) = (register(0)?, register(1)?, immediate(operands[2])?);
+            match opcode {
+                OpCode::STORE => Instruction::Store { rs1, rs2, imm },
+                OpCode::STM => Instruction::Stm { rs1, rs2, count: imm },
+                OpCode::BEQ => Instruction::Beq { rs1, rs2, imm },
+                OpCode::BNE => Instruction::Bne { rs1, rs2, imm },
+                _ => Instruction::Blt { rs1, rs2, imm },
+            }
+        }
+        Format::J => {
+            let imm = immediate(operands[0])?;
+            if opcode == OpCode::JMP { Instruction::Jmp { imm } } else { Instruction::Call { imm } }
+        }
+    })
+}
+
+fn parse_register(operand: &str, line: usize) -> Result<Register, AsmError> {
+    operand.strip_prefix('r')
+        .and_then(|index| index.parse::<u8>().ok())
+        .filter(|&index| (index as usize) < REGISTER_COUNT)
+        .map(Register)
+        .ok_or_else(|| AsmError::InvalidRegister { line, operand: operand.to_string() })
+}
+
+fn parse_immediate(operand: &str, line: usize) -> Result<Immediate, AsmError> {
+    let word: Word = parse_value::<24>(operand, line)?;
+    Ok(Immediate(word.to_int()))
+}
+
+/// Parses a decimal or `0t` trit literal into an `N` trit value, rejecting anything that doesn't fit.
+fn parse_value<const N: usize>(operand: &str, line: usize) -> Result<BalancedInt<N>, AsmError>
+where
+    BalancedInt<N>: TernaryIntegerRepr,
+{
+    let invalid = || AsmError::InvalidValue { line, operand: operand.to_string() };
+
+    if let Some(trits) = operand.strip_prefix("0t") {
+        return BalancedInt::<N>::from_str(trits).map_err(|_| invalid());
+    }
+
+    let decimal: i64 = operand.parse().map_err(|_| invalid())?;
+    let value = <BalancedInt<N> as TernaryIntegerRepr>::Int::try_from(decimal).map_err(|_| invalid())?;
+    let converted = BalancedInt::<N>::from_int(value);
+    // from_int wraps, so a value only fits if it survives the round trip.
+    if converted.to_int() != value {
+        return Err(invalid());
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use triode_arch::instruction::decode;
+
+    #[test]
+    fn test_assemble_program_with_word_table() {
+        let source = "
+            ; sum the first table entry into r1
+            load r1, r0, 3
+            addi r1, r1, 1
+            halt
+            .word 42, -7, 0t1T0   ; table
+            .tryte -5
+        ";
+        let words = assemble(source).unwrap();
+        assert_eq!(words.len(), 7);
+
+        assert_eq!(
+            decode(words[0]).unwrap(),
+            Instruction::Load { rd: Register(1), rs1: Register(0), imm: Immediate(3) }
+        );
+        assert_eq!(
+            decode(words[1]).unwrap(),
+            Instruction::Addi { rd: Register(1), rs1: Register(1), imm: Immediate(1) }
+        );
+        assert_eq!(decode(words[2]).unwrap(), Instruction::Halt);
+
+        let data: Vec<i64> = words[3..].iter().map(|w| w.to_int()).collect();
+        assert_eq!(data, vec![42, -7, 6, -5]);
+    }
+
+    #[test]
+    fn test_data_range_errors() {
+        assert!(matches!(assemble(".tryte 365"), Err(AsmError::InvalidValue { line: 1, .. })));
+        assert!(matches!(assemble(".tryte 0t1111111"), Err(AsmError::InvalidValue { line: 1, .. })));
+        assert!(matches!(assemble("nop\n.word 141214768241"), Err(AsmError::InvalidValue { line: 2, .. })));
+        assert!(assemble(".word 141214768240, -141214768240").is_ok());
+        assert!(matches!(assemble(".byte 1"), Err(AsmError::UnknownDirective { .. })));
+        assert!(matches!(assemble(".word"), Err(AsmError::OperandCount { .. })));
+    }
+
+    #[test]
+    fn test_backward_label() {
+        let words = assemble("
+                    addi r1, r0, 5
+            loop:   addi r2, r2, 3
+                    addi r1, r1, -1
+                    bne r1, r0, loop
+                    halt
+        ").unwrap();
+        assert_eq!(
+            decode(words[3]).unwrap(),
+            Instruction::Bne { rs1: Register(1), rs2: Register(0), imm: Immediate(-2) }
+        );
+    }
+
+    #[test]
+    fn test_forward_and_data_labels() {
+        let words = assemble("
+                    load r1, r0, value
+                    jmp end
+                    nop
+            end:
+                    halt
+            value:  .word 42
+        ").unwrap();
+        assert_eq!(
+            decode(words[0]).unwrap(),
+            Instruction::Load { rd: Register(1), rs1: Register(0), imm: Immediate(4) }
+        );
+        assert_eq!(decode(words[1]).unwrap(), Instruction::Jmp { imm: Immediate(2) });
+        assert_eq!(words[4].to_int(), 42);
+    }
+
+    #[test]
+    fn test_label_errors() {
+        assert!(matches!(
+            assemble("nop\njmp nowhere"),
+            Err(AsmError::UndefinedLabel { line: 2, label }) if label == "nowhere"
+        ));
+        assert!(matches!(
+            assemble("a: nop\na: halt"),
+            Err(AsmError::DuplicateLabel { line: 2, label }) if label == "a"
+        ));
+    }
+
+    #[test]
+    fn test_instruction_errors() {
+        assert!(matches!(assemble("frob r1"), Err(AsmError::UnknownMnemonic { line: 1, .. })));
+        assert!(matches!(assemble("add r1, r2"), Err(AsmError::OperandCount { expected: 3, found: 2, .. })));
+        assert!(matches!(assemble("add r1, r2, r27"), Err(AsmError::InvalidRegister { .. })));
+        assert!(matches!(assemble("addi r1, r2, 265721"), Err(AsmError::Encode { .. })));
+    }
+
+    #[test]
+    fn test_parse_instruction() {
+        let add = Instruction::parse("add", &["r1", "r2", "r3"]).unwrap();
+        assert_eq!(add, Instruction::Add { rd: Register(1), rs1: Register(2), rs2: Register(3) });
+        let store = Instruction::parse("STORE", &["r0", "r4", "0t1T"]).unwrap();
+        assert_eq!(store, Instruction::Store { rs1: Register(0), rs2: Register(4), imm: Immediate(2) });
+        assert_eq!(Instruction::parse("shr", &["r1", "r1", "r2"]).unwrap().to_string(), "shr r1, r1, r2");
+
+        assert!(matches!(
+            Instruction::parse("add", &["r1", "r2"]),
+            Err(AsmError::OperandCount { line: 0, expected: 3, found: 2 })
+        ));
+        assert!(matches!(Instruction::parse("jmp", &["loop"]), Err(AsmError::UndefinedLabel { .. })));
+    }
+}
@@ -1,3 +1,29 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: triode-asm <source.tasm>");
+        return ExitCode::FAILURE;
+    };
+
+    let source = match std::fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("failed to read {path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match triode_asm::assemble(&source) {
+        Ok(words) => {
+            for word in words {
+                println!("{word}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
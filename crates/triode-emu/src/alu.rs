@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+use bternary::balanced_int::BalancedInt;
+use triode_arch::instruction_set::MachineWord;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AluOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    /// Three way comparison, -1, 0 or 1 as `a` is less than, equal to or greater than `b`.
+    Cmp,
+    /// `a * 3^b`. The amount `b` is clamped to `0..=N` for an `N` trit word, so a negative amount
+    /// shifts by nothing and anything from `N` up clears the word.
+    Shl,
+    /// `a / 3^b` rounded to nearest, with `b` clamped like `Shl`.
+    Shr,
+}
+
+/// Computes `a op b` with wrapping arithmetic.
+/// Returns `None` when the guest should trap instead, which is division or remainder by zero.
+pub fn execute<const N: usize>(op: AluOp, a: BalancedInt<N>, b: BalancedInt<N>) -> Option<BalancedInt<N>>
+where
+    BalancedInt<N>: MachineWord,
+{
+    match op {
+        AluOp::Add => Some(a + b),
+        AluOp::Sub => Some(a - b),
+        AluOp::Mul => Some(a * b),
+        AluOp::Div => a.checked_div(b),
+        AluOp::Rem => a.checked_rem(b),
+        AluOp::Cmp => Some(match a.cmp(&b) {
+            Ordering::Less => BalancedInt::from_int(-1),
+            Ordering::Equal => BalancedInt::zero(),
+            Ordering::Greater => BalancedInt::from_int(1),
+        }),
+        AluOp::Shl => Some(a.shift_left(shift_amount(b))),
+        AluOp::Shr => Some(a.shift_right(shift_amount(b))),
+    }
+}
+
+/// A shift amount from a register, clamped to `0..=N` trits.
+fn shift_amount<const N: usize>(amount: BalancedInt<N>) -> usize
+where
+    BalancedInt<N>: MachineWord,
+{
+    amount.to_int().clamp(0, N as i64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use bternary::Word;
+    use super::*;
+
+    #[test]
+    fn test_execute() {
+        let (a, b) = (Word::from_int(17), Word::from_int(-5));
+        assert_eq!(execute(AluOp::Add, a, b), Some(Word::from_int(12)));
+        assert_eq!(execute(AluOp::Sub, a, b), Some(Word::from_int(22)));
+        assert_eq!(execute(AluOp::Mul, a, b), Some(Word::from_int(-85)));
+        assert_eq!(execute(AluOp::Div, a, b), Some(Word::from_int(-3)));
+        assert_eq!(execute(AluOp::Rem, a, b), Some(Word::from_int(2)));
+        assert_eq!(execute(AluOp::Cmp, a, b), Some(Word::from_int(1)));
+    }
+
+    #[test]
+    fn test_shift_amount_clamped() {
+        let a = Word::from_int(-17);
+        assert_eq!(execute(AluOp::Shl, a, Word::from_int(2)), Some(Word::from_int(-153)));
+        assert_eq!(execute(AluOp::Shr, a, Word::from_int(1)), Some(Word::from_int(-6)));
+        assert_eq!(execute(AluOp::Shl, a, Word::from_int(-3)), Some(a));
+        assert_eq!(execute(AluOp::Shl, a, Word::from_int(24)), Some(Word::zero()));
+        assert_eq!(execute(AluOp::Shr, a, Word::from_int(1_000)), Some(Word::zero()));
+    }
+
+    #[test]
+    fn test_division_by_zero_traps() {
+        assert_eq!(execute(AluOp::Div, Word::from_int(1), Word::zero()), None);
+        assert_eq!(execute(AluOp::Rem, Word::from_int(1), Word::zero()), None);
+    }
+}
@@ -0,0 +1,1036 @@
+use std::collections::HashMap;
+use bternary::balanced_int::BalancedInt;
+use thiserror::Error;
+use triode_arch::instruction::{decode_width, encode_width, register_run, Instruction};
+use triode_arch::instruction_set::{Immediate, InstructionSet, MachineWord, Register, REGISTER_COUNT, SP};
+use triode_arch::opcode::{OpCode, ILLEGAL_OPCODE};
+use crate::alu::{self, AluOp};
+use crate::handler::{HandlerTable, InstructionHandler};
+use crate::memory::{MemError, Memory, VecMemory};
+use crate::trap::TrapCause;
+
+/// Why the CPU stopped with an error.
+/// Faults only surface here when no trap vector is set to handle them.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CpuError<const TRITS: usize = 24> {
+    #[error("illegal instruction {0}")]
+    IllegalInstruction(BalancedInt<TRITS>),
+    #[error("division by zero")]
+    DivByZero,
+    /// A fetch, load or store the memory rejected.
+    #[error("memory fault: {0}")]
+    MemoryFault(MemError),
+    /// `run` used up its step budget before the CPU halted.
+    #[error("step limit reached before the CPU halted")]
+    CycleLimit,
+    /// `step` was called on a CPU that has already executed `halt`.
+    #[error("the CPU is halted")]
+    Halted,
+    /// `step` was called on a CPU stopped by a trap with no vector, see `Cpu::trap_cause`.
+    #[error("the CPU is stopped by an unhandled trap")]
+    Trapped,
+}
+
+impl<const TRITS: usize> From<MemError> for CpuError<TRITS> {
+    fn from(err: MemError) -> Self {
+        CpuError::MemoryFault(err)
+    }
+}
+
+impl<const TRITS: usize> From<TrapCause<TRITS>> for CpuError<TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    fn from(cause: TrapCause<TRITS>) -> Self {
+        match cause {
+            TrapCause::DivisionByZero => CpuError::DivByZero,
+            TrapCause::IllegalInstruction(word) => CpuError::IllegalInstruction(word),
+            TrapCause::MemoryFault(err) => CpuError::MemoryFault(err),
+        }
+    }
+}
+
+/// What one `step_info` call did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepInfo<const TRITS: usize = 24> {
+    /// The pc the instruction was fetched from, after any interrupt was taken.
+    pub pc: BalancedInt<TRITS>,
+    /// `None` when nothing was executed because the fetch or decode trapped.
+    pub instruction: Option<Instruction<TRITS>>,
+    /// Each register write in order. Discarded writes to `r0` aren't listed.
+    pub register_writes: Vec<(Register, BalancedInt<TRITS>)>,
+}
+
+/// A guest store to a watched address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchpointHit<const TRITS: usize = 24> {
+    /// Pc of the storing instruction.
+    pub pc: BalancedInt<TRITS>,
+    pub addr: BalancedInt<TRITS>,
+    pub old: BalancedInt<TRITS>,
+    pub new: BalancedInt<TRITS>,
+}
+
+/// The trap an error raises, or the error back if it doesn't trap.
+fn trap_cause<const TRITS: usize>(err: CpuError<TRITS>) -> Result<TrapCause<TRITS>, CpuError<TRITS>>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    match err {
+        CpuError::DivByZero => Ok(TrapCause::DivisionByZero),
+        CpuError::IllegalInstruction(word) => Ok(TrapCause::IllegalInstruction(word)),
+        CpuError::MemoryFault(err) => Ok(TrapCause::MemoryFault(err)),
+        err => Err(err),
+    }
+}
+
+/// The Triode processor.
+/// `r0` always reads as zero and writes to it are discarded.
+///
+/// When a trap vector is set, every trap records its cause and the faulting pc then jumps to
+/// the vector. Without one, the trap stops the CPU and is returned as a `CpuError`.
+///
+/// Interrupts are asynchronous: `raise_irq` latches a request which is taken at the start of
+/// the next `step` once interrupts are enabled and an interrupt vector is set. Taking it saves
+/// the pc, disables interrupts and jumps to the vector; `iret` undoes that.
+///
+/// Instructions are executed by the `InstructionHandler` registered for their opcode, see
+/// `set_handler` to replace the built-in semantics or add opcodes.
+///
+/// Registers, addresses and memory words are `TRITS` wide, a Triode Word by default. Other
+/// widths from `MIN_WORD_TRITS` up model machines built around a different word size, with
+/// immediates that grow or shrink with the word, see `decode_width`.
+#[derive(Debug, Clone)]
+pub struct Cpu<M: Memory<TRITS> = VecMemory, const TRITS: usize = 24> {
+    registers: [BalancedInt<TRITS>; REGISTER_COUNT],
+    pc: BalancedInt<TRITS>,
+    memory: M,
+    halted: bool,
+    trapped: bool,
+    trap_vector: Option<BalancedInt<TRITS>>,
+    trap_cause: Option<TrapCause<TRITS>>,
+    trap_pc: BalancedInt<TRITS>,
+    cycles: u64,
+    /// Executed opcode counts, `None` while profiling is disabled.
+    profile: Option<HashMap<OpCode, u64>>,
+    interrupts_enabled: bool,
+    interrupt_vector: Option<BalancedInt<TRITS>>,
+    pending_irq: Option<BalancedInt<TRITS>>,
+    irq_cause: Option<BalancedInt<TRITS>>,
+    interrupt_pc: BalancedInt<TRITS>,
+    handlers: HandlerTable<M, TRITS>,
+    /// Filled in by the step `step_info` is running.
+    step_record: Option<StepInfo<TRITS>>,
+    watchpoints: Vec<BalancedInt<TRITS>>,
+    watch_hits: Vec<WatchpointHit<TRITS>>,
+}
+
+impl<M: Memory<TRITS>, const TRITS: usize> Cpu<M, TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    pub fn new(memory: M) -> Self {
+        Cpu {
+            registers: [BalancedInt::zero(); REGISTER_COUNT],
+            pc: BalancedInt::zero(),
+            memory,
+            halted: false,
+            trapped: false,
+            trap_vector: None,
+            trap_cause: None,
+            trap_pc: BalancedInt::zero(),
+            cycles: 0,
+            profile: None,
+            interrupts_enabled: false,
+            interrupt_vector: None,
+            pending_irq: None,
+            irq_cause: None,
+            interrupt_pc: BalancedInt::zero(),
+            handlers: HandlerTable::builtin(),
+            step_record: None,
+            watchpoints: Vec::new(),
+            watch_hits: Vec::new(),
+        }
+    }
+
+    pub fn register(&self, register: Register) -> BalancedInt<TRITS> {
+        self.registers[register.0 as usize]
+    }
+
+    pub fn set_register(&mut self, register: Register, value: BalancedInt<TRITS>) {
+        if register.0 != 0 {
+            self.registers[register.0 as usize] = value;
+            if let Some(record) = &mut self.step_record {
+                record.register_writes.push((register, value));
+            }
+        }
+    }
+
+    pub fn pc(&self) -> BalancedInt<TRITS> {
+        self.pc
+    }
+
+    pub fn set_pc(&mut self, pc: BalancedInt<TRITS>) {
+        self.pc = pc;
+    }
+
+    pub fn memory(&self) -> &M {
+        &self.memory
+    }
+
+    pub fn memory_mut(&mut self) -> &mut M {
+        &mut self.memory
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Set when the guest trapped with no trap vector to handle it.
+    /// A trapped CPU stops executing just like a halted one.
+    pub fn is_trapped(&self) -> bool {
+        self.trapped
+    }
+
+    /// Address the CPU jumps to when an instruction traps.
+    pub fn set_trap_vector(&mut self, vector: BalancedInt<TRITS>) {
+        self.trap_vector = Some(vector);
+    }
+
+    /// The most recent trap. Only the host can read it, see `TrapCause`.
+    pub fn trap_cause(&self) -> Option<&TrapCause<TRITS>> {
+        self.trap_cause.as_ref()
+    }
+
+    /// Pc of the instruction that caused the most recent trap.
+    pub fn trap_pc(&self) -> BalancedInt<TRITS> {
+        self.trap_pc
+    }
+
+    /// Address the CPU jumps to when it takes an interrupt.
+    pub fn set_interrupt_vector(&mut self, vector: BalancedInt<TRITS>) {
+        self.interrupt_vector = Some(vector);
+    }
+
+    pub fn enable_interrupts(&mut self) {
+        self.interrupts_enabled = true;
+    }
+
+    pub fn disable_interrupts(&mut self) {
+        self.interrupts_enabled = false;
+    }
+
+    pub fn interrupts_enabled(&self) -> bool {
+        self.interrupts_enabled
+    }
+
+    /// Requests an interrupt with `cause`, replacing any request not yet taken.
+    pub fn raise_irq(&mut self, cause: BalancedInt<TRITS>) {
+        self.pending_irq = Some(cause);
+    }
+
+    /// Cause of the most recently taken interrupt.
+    pub fn irq_cause(&self) -> Option<BalancedInt<TRITS>> {
+        self.irq_cause
+    }
+
+    /// Pc the most recent interrupt will return to.
+    pub fn interrupt_pc(&self) -> BalancedInt<TRITS> {
+        self.interrupt_pc
+    }
+
+    /// Executes words whose opcode field holds `opcode` with `handler`, replacing the current
+    /// handler. A built-in opcode is `OpCode::ADD as i16`, any other value in `-364..=364` adds
+    /// a custom opcode, which costs one cycle and isn't counted by `profile`.
+    /// `ILLEGAL_OPCODE` always traps, a handler for it is never run.
+    pub fn set_handler(&mut self, opcode: i16, handler: impl InstructionHandler<M, TRITS> + Send + Sync + 'static) {
+        self.handlers.insert(opcode, std::sync::Arc::new(handler));
+    }
+
+    /// Records a `WatchpointHit` whenever an instruction stores to `addr`, and makes `run`
+    /// return after that instruction. Stores through `memory_mut` aren't watched.
+    pub fn add_watchpoint(&mut self, addr: BalancedInt<TRITS>) {
+        if !self.watchpoints.contains(&addr) {
+            self.watchpoints.push(addr);
+        }
+    }
+
+    /// Stops watching `addr`, returning whether it was watched.
+    pub fn remove_watchpoint(&mut self, addr: BalancedInt<TRITS>) -> bool {
+        let len = self.watchpoints.len();
+        self.watchpoints.retain(|&watched| watched != addr);
+        self.watchpoints.len() != len
+    }
+
+    /// Hits recorded since they were last taken, oldest first.
+    pub fn watch_hits(&self) -> &[WatchpointHit<TRITS>] {
+        &self.watch_hits
+    }
+
+    pub fn take_watch_hits(&mut self) -> Vec<WatchpointHit<TRITS>> {
+        std::mem::take(&mut self.watch_hits)
+    }
+
+    /// Cycles spent so far, the sum of `OpCode::base_cycles` over every decoded instruction.
+    /// An instruction that traps still costs its cycles.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Starts or stops counting executed opcodes. Disabling discards the counts so far.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profile = enabled.then(HashMap::new);
+    }
+
+    /// How many times each opcode was decoded since profiling was enabled.
+    /// Empty when profiling is disabled.
+    pub fn profile(&self) -> HashMap<OpCode, u64> {
+        self.profile.clone().unwrap_or_default()
+    }
+
+    /// Formats every register as a table of index, ABI name, trits and decimal value,
+    /// followed by the pc and the status flags.
+    pub fn dump_registers(&self) -> String {
+        let mut out = String::new();
+        for (index, value) in self.registers.iter().enumerate() {
+            let register = Register(index as u8);
+            out.push_str(&format!(
+                "{:<4} {:<5} {} {:>16}\n",
+                register.to_string(),
+                register.abi_name().unwrap_or(""),
+                value,
+                value.to_int()
+            ));
+        }
+        out.push_str(&format!("{:<10} {} {:>16}\n", "pc", self.pc, self.pc.to_int()));
+        out.push_str(&format!("halted={} trapped={}\n", self.halted, self.trapped));
+        out
+    }
+
+    /// Fetches, decodes and executes one instruction.
+    /// Errors with `Halted` once halted and with `Trapped` once stopped by an unhandled trap.
+    pub fn step(&mut self) -> Result<(), CpuError<TRITS>> {
+        if self.halted {
+            return Err(CpuError::Halted);
+        }
+        if self.trapped {
+            return Err(CpuError::Trapped);
+        }
+        self.take_interrupt();
+
+        match self.fetch_and_execute().map_err(trap_cause) {
+            Ok(()) => Ok(()),
+            Err(Ok(cause)) => self.trap(cause),
+            Err(Err(err)) => Err(err),
+        }
+    }
+
+    /// `step`, reporting the instruction it executed and the registers that instruction wrote.
+    pub fn step_info(&mut self) -> Result<StepInfo<TRITS>, CpuError<TRITS>> {
+        self.step_record = Some(StepInfo { pc: self.pc, instruction: None, register_writes: Vec::new() });
+        let result = self.step();
+        let info = self.step_record.take().expect("step_record is only taken here");
+        result.map(|()| info)
+    }
+
+    /// Steps until the CPU halts, erroring with `CycleLimit` if `max_steps` instructions run first.
+    /// Returns the number of instructions executed, see `cycles` for the time they took.
+    /// A CPU that is already halted returns `Ok(0)`, one stopped by a trap errors with `Trapped`.
+    ///
+    /// Also returns early, still running, after an instruction that hit a watchpoint.
+    pub fn run(&mut self, max_steps: usize) -> Result<usize, CpuError<TRITS>> {
+        if self.trapped {
+            return Err(CpuError::Trapped);
+        }
+        let mut steps = 0;
+        while !self.halted {
+            if steps == max_steps {
+                return Err(CpuError::CycleLimit);
+            }
+            let hits = self.watch_hits.len();
+            self.step()?;
+            steps += 1;
+            if self.watch_hits.len() > hits {
+                break;
+            }
+        }
+        Ok(steps)
+    }
+
+    fn fetch_and_execute(&mut self) -> Result<(), CpuError<TRITS>> {
+        let word = self.memory.load(self.pc)?;
+        let opcode_value = word.opcode_trits().to_int();
+        let handler = self.handlers.get(opcode_value)
+            .filter(|_| opcode_value != ILLEGAL_OPCODE)
+            .ok_or(CpuError::IllegalInstruction(word))?;
+
+        let instruction = match OpCode::from_trits(word.opcode_trits()) {
+            Ok(opcode) => {
+                let instruction = decode_width(word).map_err(|_| CpuError::IllegalInstruction(word))?;
+                self.cycles += u64::from(opcode.base_cycles());
+                if let Some(profile) = &mut self.profile {
+                    *profile.entry(opcode).or_insert(0) += 1;
+                }
+                instruction
+            }
+            Err(_) => {
+                self.cycles += 1;
+                Instruction::Custom { opcode: opcode_value, word }
+            }
+        };
+        #[cfg(feature = "log")]
+        log::trace!("pc={} {}", self.pc.to_int(), instruction);
+        if let Some(record) = &mut self.step_record {
+            record.pc = self.pc;
+            record.instruction = Some(instruction);
+        }
+        handler.execute(self, &instruction)
+    }
+
+    /// The built-in semantics of `instruction`, run by `BuiltinHandler`.
+    pub(crate) fn execute_builtin(&mut self, instruction: &Instruction<TRITS>) -> Result<(), CpuError<TRITS>> {
+        Ok(self.execute(*instruction)?)
+    }
+
+    /// A guest store, checked against the watchpoints.
+    fn store(&mut self, addr: BalancedInt<TRITS>, value: BalancedInt<TRITS>) -> Result<(), MemError> {
+        if !self.watchpoints.contains(&addr) {
+            return self.memory.store(addr, value);
+        }
+        let old = self.memory.load(addr)?;
+        self.memory.store(addr, value)?;
+        self.watch_hits.push(WatchpointHit { pc: self.pc, addr, old, new: value });
+        Ok(())
+    }
+
+    /// Vectors to the interrupt handler if a request is pending and interrupts can be taken.
+    fn take_interrupt(&mut self) {
+        let Some(vector) = self.interrupt_vector else { return };
+        if !self.interrupts_enabled {
+            return;
+        }
+        if let Some(cause) = self.pending_irq.take() {
+            self.irq_cause = Some(cause);
+            self.interrupt_pc = self.pc;
+            self.interrupts_enabled = false;
+            self.pc = vector;
+        }
+    }
+
+    /// Records the trap and vectors to the handler, or stops if there isn't one.
+    fn trap(&mut self, cause: TrapCause<TRITS>) -> Result<(), CpuError<TRITS>> {
+        #[cfg(feature = "log")]
+        log::debug!("trap {:?} at pc={}", cause, self.pc.to_int());
+        self.trap_cause = Some(cause.clone());
+        self.trap_pc = self.pc;
+
+        if let Some(vector) = self.trap_vector {
+            self.pc = vector;
+            return Ok(());
+        }
+
+        self.trapped = true;
+        Err(cause.into())
+    }
+
+    fn execute(&mut self, instruction: Instruction<TRITS>) -> Result<(), TrapCause<TRITS>> {
+        let mut next_pc = self.pc + BalancedInt::from_int(1);
+
+        match instruction {
+            Instruction::Nop => {}
+            Instruction::Halt => {
+                self.halted = true;
+                return Ok(());
+            }
+            Instruction::Add { rd, rs1, rs2 } => self.alu(AluOp::Add, rd, rs1, rs2)?,
+            Instruction::Sub { rd, rs1, rs2 } => self.alu(AluOp::Sub, rd, rs1, rs2)?,
+            Instruction::Mul { rd, rs1, rs2 } => self.alu(AluOp::Mul, rd, rs1, rs2)?,
+            Instruction::Div { rd, rs1, rs2 } => self.alu(AluOp::Div, rd, rs1, rs2)?,
+            Instruction::Rem { rd, rs1, rs2 } => self.alu(AluOp::Rem, rd, rs1, rs2)?,
+            Instruction::Tcmp { rd, rs1, rs2 } => self.alu(AluOp::Cmp, rd, rs1, rs2)?,
+            Instruction::Shl { rd, rs1, rs2 } => self.alu(AluOp::Shl, rd, rs1, rs2)?,
+            Instruction::Shr { rd, rs1, rs2 } => self.alu(AluOp::Shr, rd, rs1, rs2)?,
+            Instruction::Addi { rd, rs1, imm } => {
+                self.set_register(rd, self.register(rs1) + immediate(imm));
+            }
+            Instruction::Load { rd, rs1, imm } => {
+                let value = self.memory.load(self.register(rs1) + immediate(imm))
+                    .map_err(TrapCause::MemoryFault)?;
+                self.set_register(rd, value);
+            }
+            Instruction::Store { rs1, rs2, imm } => {
+                self.store(self.register(rs1) + immediate(imm), self.register(rs2))
+                    .map_err(TrapCause::MemoryFault)?;
+            }
+            // The base is read once up front, and a fault part way keeps the transfers before it.
+            Instruction::Ldm { rd, rs1, count } => {
+                let base = self.register(rs1);
+                let run = register_run(rd, count).ok_or_else(|| illegal(&instruction))?;
+                for (offset, register) in run.enumerate() {
+                    let value = self.memory.load(base + BalancedInt::from_int(offset as i64))
+                        .map_err(TrapCause::MemoryFault)?;
+                    self.set_register(register, value);
+                }
+            }
+            Instruction::Stm { rs1, rs2, count } => {
+                let base = self.register(rs1);
+                let run = register_run(rs2, count).ok_or_else(|| illegal(&instruction))?;
+                for (offset, register) in run.enumerate() {
+                    self.store(base + BalancedInt::from_int(offset as i64), self.register(register))
+                        .map_err(TrapCause::MemoryFault)?;
+                }
+            }
+            Instruction::Beq { rs1, rs2, imm } => {
+                if self.register(rs1) == self.register(rs2) {
+                    next_pc = self.pc + immediate(imm);
+                }
+            }
+            Instruction::Bne { rs1, rs2, imm } => {
+                if self.register(rs1) != self.register(rs2) {
+                    next_pc = self.pc + immediate(imm);
+                }
+            }
+            Instruction::Blt { rs1, rs2, imm } => {
+                if self.register(rs1) < self.register(rs2) {
+                    next_pc = self.pc + immediate(imm);
+                }
+            }
+            Instruction::Jmp { imm } => next_pc = self.pc + immediate(imm),
+            Instruction::Call { imm } => {
+                let sp = self.register(SP) - BalancedInt::from_int(1);
+                self.store(sp, next_pc).map_err(TrapCause::MemoryFault)?;
+                self.set_register(SP, sp);
+                next_pc = self.pc + immediate(imm);
+            }
+            Instruction::Ret => {
+                let sp = self.register(SP);
+                next_pc = self.memory.load(sp).map_err(TrapCause::MemoryFault)?;
+                self.set_register(SP, sp + BalancedInt::from_int(1));
+            }
+            Instruction::Iret => {
+                next_pc = self.interrupt_pc;
+                self.interrupts_enabled = true;
+            }
+            Instruction::Custom { .. } => return Err(illegal(&instruction)),
+        }
+
+        self.pc = next_pc;
+        Ok(())
+    }
+
+    fn alu(&mut self, op: AluOp, rd: Register, rs1: Register, rs2: Register) -> Result<(), TrapCause<TRITS>> {
+        let value = alu::execute(op, self.register(rs1), self.register(rs2))
+            .ok_or(TrapCause::DivisionByZero)?;
+        self.set_register(rd, value);
+        Ok(())
+    }
+}
+
+/// The trap for an instruction the CPU can't execute, built rather than decoded.
+fn illegal<const TRITS: usize>(instruction: &Instruction<TRITS>) -> TrapCause<TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    TrapCause::IllegalInstruction(encode_width(instruction).unwrap_or_default())
+}
+
+/// Every immediate field fits in the word it was read from.
+fn immediate<const TRITS: usize>(imm: Immediate) -> BalancedInt<TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    BalancedInt::from_int(imm.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use bternary::Word;
+    use triode_arch::instruction::decode;
+    use super::*;
+    use triode_asm::assemble;
+
+    fn cpu_for(source: &str) -> Cpu {
+        let program = assemble(source).unwrap();
+        Cpu::new(VecMemory::with_program(&program, 64))
+    }
+
+    #[test]
+    fn test_countdown_loop() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 5
+            addi r2, r2, 3      ; loop body: r2 += 3
+            addi r1, r1, -1
+            bne r1, r0, -2
+            halt
+        ");
+        cpu.run(100).unwrap();
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.register(Register(2)).to_int(), 15);
+        assert_eq!(cpu.pc().to_int(), 4);
+    }
+
+    #[test]
+    fn test_narrow_word_cpu() {
+        let source = "
+            addi r1, r0, 5
+            addi r2, r2, 3
+            addi r1, r1, -1
+            bne r1, r0, -2
+            addi r3, r0, 243
+            mul r3, r3, r3      ; 3^10
+            mul r3, r3, r3      ; 3^20, past the top of an 18 trit word
+            halt
+        ";
+        let program = assemble(source).unwrap();
+        let narrow: Vec<BalancedInt<18>> = program.iter()
+            .map(|&word| encode_width(&decode(word).unwrap().resize()).unwrap())
+            .collect();
+        let mut cpu = Cpu::new(VecMemory::with_program(&narrow, 16));
+        cpu.run(100).unwrap();
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.register(Register(2)).to_int(), 15);
+        // 3^20 is a 1 followed by 20 zeros, leaving nothing in the low 18 trits.
+        assert_eq!(cpu.register(Register(3)).to_int(), 0);
+
+        let mut wide = Cpu::new(VecMemory::with_program(&program, 16));
+        wide.run(100).unwrap();
+        assert_eq!(wide.register(Register(2)).to_int(), 15);
+        assert_eq!(wide.register(Register(3)).to_int(), 3_486_784_401);
+    }
+
+    #[test]
+    fn test_cycle_count() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 2
+            mul r2, r1, r1
+            addi r1, r1, -1
+            bne r1, r0, -2
+            store r0, r2, 10
+            halt
+        ");
+        let steps = cpu.run(100).unwrap();
+        assert_eq!(steps, 9);
+
+        let executed = [
+            OpCode::ADDI,
+            OpCode::MUL, OpCode::ADDI, OpCode::BNE,
+            OpCode::MUL, OpCode::ADDI, OpCode::BNE,
+            OpCode::STORE,
+            OpCode::HALT,
+        ];
+        let expected: u64 = executed.iter().map(|op| u64::from(op.base_cycles())).sum();
+        assert_eq!(cpu.cycles(), expected);
+    }
+
+    #[test]
+    fn test_profile() {
+        let source = "
+            addi r1, r0, 50
+            addi r2, r2, 3
+            addi r1, r1, -1
+            bne r1, r0, -2
+            halt
+        ";
+        let mut cpu = cpu_for(source);
+        cpu.run(1000).unwrap();
+        assert!(cpu.profile().is_empty());
+
+        let mut cpu = cpu_for(source);
+        cpu.set_profiling(true);
+        cpu.run(1000).unwrap();
+        let profile = cpu.profile();
+        assert_eq!(profile[&OpCode::ADDI], 101);
+        assert_eq!(profile[&OpCode::BNE], 50);
+        assert_eq!(profile[&OpCode::HALT], 1);
+        let hottest = profile.iter().max_by_key(|(_, count)| **count).unwrap();
+        assert_eq!(*hottest.0, OpCode::ADDI);
+    }
+
+    #[test]
+    fn test_irq_runs_handler_then_returns() {
+        let mut cpu = cpu_for("
+            addi r1, r1, 1      ; main loop counts in r1
+            addi r1, r1, 1
+            addi r1, r1, 1
+            halt
+            .word 0
+            addi r3, r0, 7      ; interrupt handler at address 5
+            iret
+        ");
+        cpu.set_interrupt_vector(Word::from_int(5));
+        cpu.enable_interrupts();
+
+        cpu.step().unwrap();
+        cpu.raise_irq(Word::from_int(42));
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc().to_int(), 6);
+        assert!(!cpu.interrupts_enabled());
+        assert_eq!(cpu.irq_cause(), Some(Word::from_int(42)));
+        assert_eq!(cpu.interrupt_pc().to_int(), 1);
+
+        cpu.run(100).unwrap();
+        assert!(cpu.is_halted());
+        assert!(cpu.interrupts_enabled());
+        assert_eq!(cpu.register(Register(3)).to_int(), 7);
+        assert_eq!(cpu.register(Register(1)).to_int(), 3);
+    }
+
+    #[test]
+    fn test_irq_waits_until_enabled() {
+        let mut cpu = cpu_for("
+            nop
+            nop
+            halt
+            addi r3, r0, 7      ; interrupt handler at address 3
+            iret
+        ");
+        cpu.set_interrupt_vector(Word::from_int(3));
+        cpu.raise_irq(Word::from_int(1));
+        cpu.step().unwrap();
+        assert_eq!(cpu.pc().to_int(), 1);
+        assert_eq!(cpu.irq_cause(), None);
+
+        cpu.enable_interrupts();
+        cpu.run(100).unwrap();
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.register(Register(3)).to_int(), 7);
+    }
+
+    #[test]
+    fn test_tcmp() {
+        let mut cpu = cpu_for("
+            addi r1, r0, -7
+            addi r2, r0, 4
+            tcmp r3, r1, r2
+            tcmp r4, r2, r1
+            tcmp r5, r2, r2
+            halt
+        ");
+        cpu.run(10).unwrap();
+        assert_eq!(cpu.register(Register(3)).to_int(), -1);
+        assert_eq!(cpu.register(Register(4)).to_int(), 1);
+        assert_eq!(cpu.register(Register(5)).to_int(), 0);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log_records() {
+        use std::cell::RefCell;
+
+        // Records are kept per thread so tests running in parallel don't see each other's.
+        thread_local! {
+            static RECORDS: RefCell<Vec<(log::Level, String)>> = const { RefCell::new(Vec::new()) };
+        }
+        struct TestLogger;
+        impl log::Log for TestLogger {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                RECORDS.with(|r| r.borrow_mut().push((record.level(), record.args().to_string())));
+            }
+            fn flush(&self) {}
+        }
+        let _ = log::set_logger(&TestLogger);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut cpu = cpu_for("
+            addi r1, r0, 2
+            addi r1, r1, -1
+            bne r1, r0, -1
+            div r2, r1, r0
+        ");
+        assert_eq!(cpu.run(100), Err(CpuError::DivByZero));
+
+        let records = RECORDS.with(|r| r.take());
+        let traces: Vec<&String> = records.iter()
+            .filter(|(level, _)| *level == log::Level::Trace)
+            .map(|(_, message)| message)
+            .collect();
+        assert_eq!(traces.len(), 6);
+        assert_eq!(traces[0], "pc=0 addi r1, r0, 2");
+        assert_eq!(traces[5], "pc=3 div r2, r1, r0");
+        assert!(records.iter().any(|(level, message)| {
+            *level == log::Level::Debug && message == "trap DivisionByZero at pc=3"
+        }));
+    }
+
+    #[test]
+    fn test_step_info() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 5
+            addi r0, r1, 1
+            addi r2, r0, 10
+            call 1
+            halt
+        ");
+        let info = cpu.step_info().unwrap();
+        assert_eq!(info.pc.to_int(), 0);
+        assert_eq!(info.instruction.unwrap().to_string(), "addi r1, r0, 5");
+        assert_eq!(info.register_writes, [(Register(1), Word::from_int(5))]);
+
+        // The write to r0 is discarded so it isn't reported.
+        assert!(cpu.step_info().unwrap().register_writes.is_empty());
+        cpu.step_info().unwrap();
+        let info = cpu.step_info().unwrap();
+        assert_eq!(info.pc.to_int(), 3);
+        assert_eq!(info.register_writes, [(SP, Word::from_int(9))]);
+        assert_eq!(cpu.step_info().unwrap().instruction, Some(Instruction::Halt));
+        assert_eq!(cpu.step_info(), Err(CpuError::Halted));
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 7
+            store r0, r1, 40
+            addi r1, r0, -2
+            store r0, r1, 41
+            store r0, r1, 40
+            halt
+        ");
+        cpu.add_watchpoint(Word::from_int(40));
+        cpu.add_watchpoint(Word::from_int(41));
+        cpu.add_watchpoint(Word::from_int(40));
+
+        assert_eq!(cpu.run(100), Ok(2));
+        assert!(!cpu.is_halted());
+        assert_eq!(cpu.memory().load(Word::from_int(40)).unwrap().to_int(), 7);
+        assert_eq!(cpu.watch_hits(), [WatchpointHit {
+            pc: Word::from_int(1),
+            addr: Word::from_int(40),
+            old: Word::zero(),
+            new: Word::from_int(7),
+        }]);
+
+        assert!(cpu.remove_watchpoint(Word::from_int(41)));
+        assert!(!cpu.remove_watchpoint(Word::from_int(41)));
+        assert_eq!(cpu.run(100), Ok(3));
+        let hits = cpu.take_watch_hits();
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[1].old.to_int(), hits[1].new.to_int()), (7, -2));
+        assert!(cpu.watch_hits().is_empty());
+        assert_eq!(cpu.run(100), Ok(1));
+        assert!(cpu.is_halted());
+    }
+
+    #[test]
+    fn test_ldm_stm() {
+        let mut cpu = cpu_for("
+            addi r3, r0, 11
+            addi r4, r0, -22
+            addi r5, r0, 33
+            addi r1, r0, 40
+            stm r1, r3, 3
+            ldm r10, r1, 3
+            halt
+        ");
+        cpu.run(100).unwrap();
+        for (offset, value) in [11, -22, 33].into_iter().enumerate() {
+            assert_eq!(cpu.memory().load(Word::from_int(40 + offset as i64)).unwrap().to_int(), value);
+            assert_eq!(cpu.register(Register(10 + offset as u8)).to_int(), value);
+        }
+        assert_eq!(cpu.register(Register(13)), Word::zero());
+    }
+
+    #[test]
+    fn test_ldm_invalid_run_traps() {
+        let mut cpu = Cpu::new(VecMemory::new(8));
+        let ldm = Instruction::Ldm { rd: Register(26), rs1: Register(0), count: Immediate(2) };
+        assert!(matches!(cpu.execute_builtin(&ldm), Err(CpuError::IllegalInstruction(_))));
+    }
+
+    #[test]
+    fn test_shift_by_register() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 5
+            addi r2, r0, 3
+            shl r3, r1, r2
+            addi r4, r0, -1
+            shr r5, r3, r4
+            addi r2, r0, 2
+            shr r6, r3, r2
+            halt
+        ");
+        cpu.run(10).unwrap();
+        assert_eq!(cpu.register(Register(3)).to_int(), 135);
+        // A negative amount is clamped to no shift.
+        assert_eq!(cpu.register(Register(5)).to_int(), 135);
+        assert_eq!(cpu.register(Register(6)).to_int(), 15);
+    }
+
+    #[test]
+    fn test_dump_registers() {
+        let mut cpu = cpu_for("
+            addi r3, r0, -1234
+            halt
+        ");
+        cpu.run(10).unwrap();
+        let dump = cpu.dump_registers();
+
+        assert_eq!(dump.lines().count(), REGISTER_COUNT + 2);
+        let a0 = dump.lines().nth(3).unwrap();
+        assert!(a0.starts_with("r3   a0    "));
+        assert!(a0.contains(&Word::from_int(-1234).to_string()));
+        assert!(a0.ends_with(" -1234"));
+        assert!(dump.contains("halted=true trapped=false"));
+    }
+
+    #[test]
+    fn test_load_store() {
+        let mut cpu = cpu_for("
+            load r1, r0, 4
+            addi r1, r1, 1
+            store r0, r1, 5
+            halt
+            .word 41
+        ");
+        cpu.run(10).unwrap();
+        assert_eq!(cpu.memory().load(Word::from_int(5)).unwrap().to_int(), 42);
+    }
+
+    #[test]
+    fn test_r0_is_hardwired_zero() {
+        let mut cpu = cpu_for("addi r0, r0, 7\nhalt");
+        cpu.run(10).unwrap();
+        assert!(cpu.register(Register(0)).is_zero());
+    }
+
+    #[test]
+    fn test_division_by_zero_traps() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 10
+            div r2, r1, r0
+            halt
+        ");
+        assert_eq!(cpu.run(10), Err(CpuError::DivByZero));
+        assert!(cpu.is_trapped());
+        assert!(!cpu.is_halted());
+        assert_eq!(cpu.pc().to_int(), 1);
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::DivisionByZero));
+    }
+
+    #[test]
+    fn test_division_by_zero_vectors_to_handler() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 10
+            div r2, r1, r0
+            addi r4, r0, 1      ; skipped, the handler halts
+            halt
+            .word 0
+            addi r3, r0, 99     ; trap handler at address 5
+            halt
+        ");
+        cpu.set_trap_vector(Word::from_int(5));
+        cpu.run(10).unwrap();
+
+        assert!(cpu.is_halted());
+        assert!(!cpu.is_trapped());
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::DivisionByZero));
+        assert_eq!(cpu.trap_pc().to_int(), 1);
+        assert_eq!(cpu.register(Register(3)).to_int(), 99);
+        assert!(cpu.register(Register(4)).is_zero());
+    }
+
+    #[test]
+    fn test_faults_vector_when_handled() {
+        let mut cpu = cpu_for("
+            load r1, r0, 1000
+            halt
+        ");
+        cpu.set_trap_vector(Word::from_int(1));
+        cpu.run(10).unwrap();
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::MemoryFault(MemError::OutOfBounds(1000))));
+        assert_eq!(cpu.trap_cause().unwrap().code(), 3);
+
+        let mut cpu = Cpu::new(VecMemory::with_program(&[Word::from_int(-1)], 4));
+        cpu.set_trap_vector(Word::from_int(2));
+        cpu.step().unwrap();
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::IllegalInstruction(Word::from_int(-1))));
+        assert_eq!(cpu.pc().to_int(), 2);
+    }
+
+    #[test]
+    fn test_faults_are_errors() {
+        let mut cpu = cpu_for("load r1, r0, 1000");
+        assert_eq!(cpu.step(), Err(CpuError::MemoryFault(MemError::OutOfBounds(1000))));
+        assert!(cpu.is_trapped());
+
+        let mut cpu = cpu_for("store r0, r0, -3");
+        assert_eq!(cpu.run(10), Err(CpuError::MemoryFault(MemError::OutOfBounds(-3))));
+
+        let mut cpu = Cpu::new(VecMemory::with_program(&[Word::from_int(-1)], 4));
+        assert_eq!(cpu.step(), Err(CpuError::IllegalInstruction(Word::from_int(-1))));
+
+        let mut cpu = cpu_for("div r1, r1, r0");
+        assert_eq!(cpu.step(), Err(CpuError::DivByZero));
+    }
+
+    /// Memory that treats every address from 10 up as misaligned.
+    struct MisalignedAbove10(VecMemory);
+
+    impl Memory for MisalignedAbove10 {
+        fn size(&self) -> usize {
+            self.0.size()
+        }
+
+        fn load(&self, addr: Word) -> Result<Word, MemError> {
+            match addr.to_int() {
+                addr @ 10.. => Err(MemError::Misaligned(addr)),
+                _ => self.0.load(addr),
+            }
+        }
+
+        fn store(&mut self, addr: Word, value: Word) -> Result<(), MemError> {
+            match addr.to_int() {
+                addr @ 10.. => Err(MemError::Misaligned(addr)),
+                _ => self.0.store(addr, value),
+            }
+        }
+    }
+
+    #[test]
+    fn test_misaligned_faults_keep_their_cause() {
+        let program = assemble("jmp 12\nhalt").unwrap();
+        let mut cpu = Cpu::new(MisalignedAbove10(VecMemory::with_program(&program, 64)));
+        cpu.step().unwrap();
+        assert_eq!(cpu.step(), Err(CpuError::MemoryFault(MemError::Misaligned(12))));
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::MemoryFault(MemError::Misaligned(12))));
+
+        let mut cpu = Cpu::new(MisalignedAbove10(VecMemory::with_program(&program, 64)));
+        cpu.set_trap_vector(Word::from_int(1));
+        cpu.run(10).unwrap();
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::MemoryFault(MemError::Misaligned(12))));
+        assert_eq!(cpu.trap_pc().to_int(), 12);
+
+        let program = assemble("addi r1, r0, 11\nstore r1, r1, 0\nhalt").unwrap();
+        let mut cpu = Cpu::new(MisalignedAbove10(VecMemory::with_program(&program, 64)));
+        assert_eq!(cpu.run(10), Err(CpuError::MemoryFault(MemError::Misaligned(11))));
+    }
+
+    #[test]
+    fn test_stepping_trapped_cpu_errors() {
+        let mut cpu = cpu_for("div r1, r1, r0\nhalt");
+        assert_eq!(cpu.step(), Err(CpuError::DivByZero));
+        assert_eq!(cpu.step(), Err(CpuError::Trapped));
+        assert_eq!(cpu.step_info(), Err(CpuError::Trapped));
+        assert_eq!(cpu.pc().to_int(), 0);
+        assert_eq!(cpu.trap_cause(), Some(&TrapCause::DivisionByZero));
+        assert_eq!(cpu.run(10), Err(CpuError::Trapped));
+        assert_eq!(cpu.run(0), Err(CpuError::Trapped));
+    }
+
+    #[test]
+    fn test_run_limits() {
+        let mut cpu = cpu_for("jmp 0");
+        assert_eq!(cpu.run(50), Err(CpuError::CycleLimit));
+        assert!(!cpu.is_halted());
+
+        let mut cpu = cpu_for("halt");
+        assert_eq!(cpu.run(10), Ok(1));
+        assert_eq!(cpu.step(), Err(CpuError::Halted));
+        assert_eq!(cpu.run(10), Ok(0));
+    }
+}
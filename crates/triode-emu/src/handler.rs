@@ -0,0 +1,181 @@
+//! Pluggable instruction semantics.
+//!
+//! `Cpu::step` looks up the handler for each fetched word by its opcode field value. Every
+//! built-in opcode starts out with the built-in handler, and `Cpu::set_handler` can replace it or
+//! claim an opcode value no built-in instruction uses.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use bternary::balanced_int::BalancedInt;
+use triode_arch::instruction::Instruction;
+use triode_arch::instruction_set::MachineWord;
+use triode_arch::opcode::OpCode;
+use crate::cpu::{Cpu, CpuError};
+use crate::memory::{Memory, VecMemory};
+
+/// Executes one instruction against the CPU.
+///
+/// Handlers run after the fetch, decode and cycle accounting, and own everything from there on,
+/// including moving the pc. Built-in opcodes receive their decoded instruction, custom opcode
+/// values receive `Instruction::Custom` with the raw word to read operands from.
+///
+/// `DivByZero`, `IllegalInstruction` and `MemoryFault` errors trap like the built-in ones do,
+/// other errors are returned from `step` as is.
+pub trait InstructionHandler<M: Memory<TRITS> = VecMemory, const TRITS: usize = 24> {
+    fn execute(&self, cpu: &mut Cpu<M, TRITS>, instruction: &Instruction<TRITS>) -> Result<(), CpuError<TRITS>>;
+}
+
+/// The semantics every built-in opcode starts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuiltinHandler;
+
+impl<M: Memory<TRITS>, const TRITS: usize> InstructionHandler<M, TRITS> for BuiltinHandler
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    fn execute(&self, cpu: &mut Cpu<M, TRITS>, instruction: &Instruction<TRITS>) -> Result<(), CpuError<TRITS>> {
+        cpu.execute_builtin(instruction)
+    }
+}
+
+pub(crate) type SharedHandler<M, const TRITS: usize> = Arc<dyn InstructionHandler<M, TRITS> + Send + Sync>;
+
+/// Handlers by opcode field value.
+pub(crate) struct HandlerTable<M: Memory<TRITS>, const TRITS: usize>(HashMap<i16, SharedHandler<M, TRITS>>);
+
+impl<M: Memory<TRITS>, const TRITS: usize> HandlerTable<M, TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    pub(crate) fn builtin() -> Self {
+        let builtin: SharedHandler<M, TRITS> = Arc::new(BuiltinHandler);
+        HandlerTable(OpCode::ALL.iter().map(|&op| (op as i16, builtin.clone())).collect())
+    }
+
+    pub(crate) fn get(&self, opcode: i16) -> Option<SharedHandler<M, TRITS>> {
+        self.0.get(&opcode).cloned()
+    }
+
+    pub(crate) fn insert(&mut self, opcode: i16, handler: SharedHandler<M, TRITS>) {
+        self.0.insert(opcode, handler);
+    }
+}
+
+impl<M: Memory<TRITS>, const TRITS: usize> Clone for HandlerTable<M, TRITS> {
+    fn clone(&self) -> Self {
+        HandlerTable(self.0.clone())
+    }
+}
+
+impl<M: Memory<TRITS>, const TRITS: usize> std::fmt::Debug for HandlerTable<M, TRITS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut opcodes: Vec<i16> = self.0.keys().copied().collect();
+        opcodes.sort();
+        f.debug_tuple("HandlerTable").field(&opcodes).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bternary::Word;
+    use triode_arch::instruction_set::{InstructionSet, Register, OPCODE_FIELD};
+    use triode_asm::assemble;
+    use super::*;
+
+    /// `rd = rd * 2` under a custom opcode, operands read from the raw word.
+    struct Double;
+
+    impl<M: Memory> InstructionHandler<M> for Double {
+        fn execute(&self, cpu: &mut Cpu<M>, instruction: &Instruction) -> Result<(), CpuError> {
+            let Instruction::Custom { word, .. } = *instruction else {
+                return Err(CpuError::IllegalInstruction(Word::zero()));
+            };
+            let rd = word.rd();
+            cpu.set_register(rd, cpu.register(rd) + cpu.register(rd));
+            cpu.set_pc(cpu.pc() + Word::from_int(1));
+            Ok(())
+        }
+    }
+
+    const DOUBLE: i16 = 100;
+
+    fn double(rd: Register) -> Word {
+        let mut word = Word::zero();
+        OPCODE_FIELD.write(&mut word, DOUBLE as i64).unwrap();
+        word.write_rd(rd).unwrap();
+        word
+    }
+
+    #[test]
+    fn test_custom_opcode() {
+        let mut program = assemble("addi r4, r0, 21").unwrap();
+        program.push(double(Register(4)));
+        program.extend(assemble("halt").unwrap());
+
+        let mut cpu = Cpu::new(VecMemory::with_program(&program, 8));
+        assert_eq!(cpu.clone().run(10), Err(CpuError::IllegalInstruction(double(Register(4)))));
+
+        cpu.set_handler(DOUBLE, Double);
+        cpu.run(10).unwrap();
+        assert_eq!(cpu.register(Register(4)).to_int(), 42);
+    }
+
+    #[test]
+    fn test_illegal_opcode_ignores_handler() {
+        let illegal = Word::from_int(triode_arch::opcode::ILLEGAL_OPCODE as i64);
+        let mut cpu = Cpu::new(VecMemory::with_program(&[illegal], 8));
+        cpu.set_handler(triode_arch::opcode::ILLEGAL_OPCODE, Double);
+        assert_eq!(cpu.step(), Err(CpuError::IllegalInstruction(illegal)));
+    }
+
+    /// `rd = word` under a custom opcode, for any word width.
+    struct LoadWord;
+
+    impl<M: Memory<TRITS>, const TRITS: usize> InstructionHandler<M, TRITS> for LoadWord
+    where
+        BalancedInt<TRITS>: MachineWord,
+    {
+        fn execute(&self, cpu: &mut Cpu<M, TRITS>, instruction: &Instruction<TRITS>) -> Result<(), CpuError<TRITS>> {
+            let Instruction::Custom { word, .. } = *instruction else { unreachable!() };
+            cpu.set_register(word.rd(), word);
+            cpu.set_pc(cpu.pc() + BalancedInt::from_int(1));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_custom_opcode_on_wide_machine() {
+        let mut word = BalancedInt::<36>::from_int(3_i64.pow(30));
+        OPCODE_FIELD.write(&mut word, DOUBLE as i64).unwrap();
+        word.write_rd(Register(4)).unwrap();
+        let mut cpu = Cpu::new(VecMemory::with_program(&[word], 4));
+        cpu.set_handler(DOUBLE, LoadWord);
+        cpu.step().unwrap();
+        assert_eq!(cpu.register(Register(4)), word);
+    }
+
+    /// Makes `add` subtract instead.
+    struct AddSubtracts;
+
+    impl<M: Memory> InstructionHandler<M> for AddSubtracts {
+        fn execute(&self, cpu: &mut Cpu<M>, instruction: &Instruction) -> Result<(), CpuError> {
+            let Instruction::Add { rd, rs1, rs2 } = *instruction else { unreachable!() };
+            let sub = Instruction::Sub { rd, rs1, rs2 };
+            BuiltinHandler.execute(cpu, &sub)
+        }
+    }
+
+    #[test]
+    fn test_override_builtin() {
+        let program = assemble("
+            addi r1, r0, 10
+            addi r2, r0, 3
+            add r3, r1, r2
+            halt
+        ").unwrap();
+        let mut cpu = Cpu::new(VecMemory::with_program(&program, 8));
+        cpu.set_handler(OpCode::ADD as i16, AddSubtracts);
+        cpu.run(10).unwrap();
+        assert_eq!(cpu.register(Register(3)).to_int(), 7);
+    }
+}
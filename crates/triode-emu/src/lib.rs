@@ -1,14 +1,12 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
+pub mod alu;
+pub mod cpu;
+pub mod handler;
+pub mod memory;
+pub mod snapshot;
+pub mod trap;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub use cpu::{Cpu, CpuError, StepInfo, WatchpointHit};
+pub use handler::{BuiltinHandler, InstructionHandler};
+pub use memory::{dump_memory, MemError, Memory, TryteMemory, VecMemory, WordAddr};
+pub use snapshot::{CpuSnapshot, SnapshotDelta};
+pub use trap::TrapCause;
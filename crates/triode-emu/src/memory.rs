@@ -0,0 +1,336 @@
+use bternary::balanced_int::{ArithmeticTernaryInteger, BalancedInt, TernaryIntegerRepr};
+use bternary::word::TRYTES_IN_WORD;
+use bternary::{Tryte, Word};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MemError {
+    #[error("address {0} is out of bounds")]
+    OutOfBounds(i64),
+    /// A word access to a `TryteMemory` address that isn't a multiple of 4.
+    #[error("address {0} is not word aligned")]
+    Misaligned(i64),
+}
+
+/// Address conversion for Words. `Word` lives in `bternary`, which knows nothing of memory errors,
+/// so this is an extension trait rather than an inherent method.
+pub trait WordAddr {
+    /// The Word as a nonnegative address, erroring with `OutOfBounds` if it is negative.
+    /// Says nothing about the upper bound, which is up to the memory.
+    fn to_usize_addr(&self) -> Result<usize, MemError>;
+}
+
+impl<const N: usize> WordAddr for BalancedInt<N>
+where
+    BalancedInt<N>: TernaryIntegerRepr<Int = i64>,
+{
+    fn to_usize_addr(&self) -> Result<usize, MemError> {
+        let value = self.to_int();
+        usize::try_from(value).map_err(|_| MemError::OutOfBounds(value))
+    }
+}
+
+/// Word addressed memory of `TRITS` wide words, Triode Words unless stated otherwise.
+pub trait Memory<const TRITS: usize = 24> {
+    /// Number of addressable words, valid addresses are `0..size()`.
+    fn size(&self) -> usize;
+    fn load(&self, addr: BalancedInt<TRITS>) -> Result<BalancedInt<TRITS>, MemError>;
+    fn store(&mut self, addr: BalancedInt<TRITS>, value: BalancedInt<TRITS>) -> Result<(), MemError>;
+}
+
+/// Memory backed by a flat `Vec` of words, address `n` is index `n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VecMemory<const TRITS: usize = 24> {
+    words: Vec<BalancedInt<TRITS>>,
+}
+
+impl VecMemory {
+    /// Zeroed memory of `size` Words, see `zeroed` for other word widths.
+    pub fn new(size: usize) -> Self {
+        VecMemory::zeroed(size)
+    }
+}
+
+impl<const TRITS: usize> VecMemory<TRITS>
+where
+    BalancedInt<TRITS>: TernaryIntegerRepr<Int = i64>,
+{
+    /// Zeroed memory of `size` words.
+    pub fn zeroed(size: usize) -> Self {
+        VecMemory { words: vec![BalancedInt::zero(); size] }
+    }
+
+    /// Memory of `size` words with `program` copied to address 0.
+    /// Grows to fit the program if it is larger than `size`.
+    pub fn with_program(program: &[BalancedInt<TRITS>], size: usize) -> Self {
+        let mut words = program.to_vec();
+        if words.len() < size {
+            words.resize(size, BalancedInt::zero());
+        }
+        VecMemory { words }
+    }
+
+    /// Sets `count` words starting at `start` to `value`.
+    /// Errors without writing anything if any of them is out of bounds.
+    pub fn fill(&mut self, start: BalancedInt<TRITS>, count: usize, value: BalancedInt<TRITS>) -> Result<(), MemError> {
+        let range = self.range(start, count)?;
+        self.words[range].fill(value);
+        Ok(())
+    }
+
+    /// Copies `count` words from `src` to `dst`. The ranges may overlap, the copy behaves as if
+    /// the source were read in full before anything is written, like `slice::copy_within`.
+    /// Errors without writing anything if either range is out of bounds.
+    pub fn copy_within(&mut self, src: BalancedInt<TRITS>, dst: BalancedInt<TRITS>, count: usize) -> Result<(), MemError> {
+        let source = self.range(src, count)?;
+        let dest = self.range(dst, count)?;
+        self.words.copy_within(source, dest.start);
+        Ok(())
+    }
+
+    /// Indices of `count` words starting at `start`, erroring with the first address out of bounds.
+    fn range(&self, start: BalancedInt<TRITS>, count: usize) -> Result<std::ops::Range<usize>, MemError> {
+        if count == 0 {
+            return Ok(0..0);
+        }
+        let first = self.index(start)?;
+        let available = self.words.len() - first;
+        if count > available {
+            return Err(MemError::OutOfBounds(start.to_int() + available as i64));
+        }
+        Ok(first..first + count)
+    }
+
+    fn index(&self, addr: BalancedInt<TRITS>) -> Result<usize, MemError> {
+        let index = addr.to_usize_addr()?;
+        if index >= self.words.len() {
+            return Err(MemError::OutOfBounds(addr.to_int()));
+        }
+        Ok(index)
+    }
+}
+
+impl<const TRITS: usize> Memory<TRITS> for VecMemory<TRITS>
+where
+    BalancedInt<TRITS>: TernaryIntegerRepr<Int = i64>,
+{
+    fn size(&self) -> usize {
+        self.words.len()
+    }
+
+    fn load(&self, addr: BalancedInt<TRITS>) -> Result<BalancedInt<TRITS>, MemError> {
+        Ok(self.words[self.index(addr)?])
+    }
+
+    fn store(&mut self, addr: BalancedInt<TRITS>, value: BalancedInt<TRITS>) -> Result<(), MemError> {
+        let index = self.index(addr)?;
+        self.words[index] = value;
+        Ok(())
+    }
+}
+
+/// Tryte addressed memory, where a word access reads or writes four consecutive trytes.
+///
+/// Words are little endian: the tryte at the word's address holds its least significant trits,
+/// in the order of `Word::to_trytes`. Word accesses must be aligned, at an address that is a
+/// multiple of 4, and error with `Misaligned` otherwise. This isn't a `Memory`, the CPU expects
+/// consecutive words one address apart rather than four.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryteMemory {
+    trytes: Vec<Tryte>,
+}
+
+impl TryteMemory {
+    /// Zeroed memory of `size` trytes.
+    pub fn new(size: usize) -> Self {
+        TryteMemory { trytes: vec![Tryte::zero(); size] }
+    }
+
+    /// Number of addressable trytes, valid addresses are `0..size()`.
+    pub fn size(&self) -> usize {
+        self.trytes.len()
+    }
+
+    pub fn load_tryte(&self, addr: Word) -> Result<Tryte, MemError> {
+        Ok(self.trytes[self.index(addr)?])
+    }
+
+    pub fn store_tryte(&mut self, addr: Word, value: Tryte) -> Result<(), MemError> {
+        let index = self.index(addr)?;
+        self.trytes[index] = value;
+        Ok(())
+    }
+
+    /// The word whose least significant tryte is at `addr`.
+    pub fn load_word(&self, addr: Word) -> Result<Word, MemError> {
+        let start = self.word_index(addr)?;
+        let trytes = self.trytes[start..start + TRYTES_IN_WORD].try_into().expect("a word of trytes");
+        Ok(Word::from_trytes(trytes))
+    }
+
+    /// Writes the four trytes of `value` from `addr` up, or nothing if any is out of bounds.
+    pub fn store_word(&mut self, addr: Word, value: Word) -> Result<(), MemError> {
+        let start = self.word_index(addr)?;
+        self.trytes[start..start + TRYTES_IN_WORD].copy_from_slice(&value.to_trytes());
+        Ok(())
+    }
+
+    fn index(&self, addr: Word) -> Result<usize, MemError> {
+        let index = addr.to_usize_addr()?;
+        if index >= self.trytes.len() {
+            return Err(MemError::OutOfBounds(addr.to_int()));
+        }
+        Ok(index)
+    }
+
+    /// Index of the first tryte of the aligned word at `addr`, erroring with the first tryte
+    /// address out of bounds.
+    fn word_index(&self, addr: Word) -> Result<usize, MemError> {
+        let start = addr.to_usize_addr()?;
+        if start % TRYTES_IN_WORD != 0 {
+            return Err(MemError::Misaligned(addr.to_int()));
+        }
+        if start + TRYTES_IN_WORD > self.trytes.len() {
+            return Err(MemError::OutOfBounds(start.max(self.trytes.len()) as i64));
+        }
+        Ok(start)
+    }
+}
+
+/// Formats `count` words starting at `start` as a table of address, trits and decimal value.
+/// Addresses wrap around the Word range like any other Word addition, and an address that
+/// can't be loaded prints its error in place of the value instead of ending the dump.
+pub fn dump_memory<const TRITS: usize>(mem: &impl Memory<TRITS>, start: BalancedInt<TRITS>, count: usize) -> String
+where
+    BalancedInt<TRITS>: TernaryIntegerRepr<Int = i64> + ArithmeticTernaryInteger,
+{
+    let mut out = String::new();
+    let mut addr = start;
+    for _ in 0..count {
+        match mem.load(addr) {
+            Ok(value) => out.push_str(&format!("{:>16} {} {:>16}\n", addr.to_int(), value, value.to_int())),
+            Err(err) => out.push_str(&format!("{:>16} <{}>\n", addr.to_int(), err)),
+        }
+        addr += BalancedInt::from_int(1);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_store() {
+        let mut memory = VecMemory::new(4);
+        memory.store(Word::from_int(3), Word::from_int(-42)).unwrap();
+        assert_eq!(memory.load(Word::from_int(3)).unwrap().to_int(), -42);
+        assert_eq!(memory.load(Word::from_int(0)).unwrap(), Word::zero());
+    }
+
+    #[test]
+    fn test_to_usize_addr() {
+        assert_eq!(Word::from_int(0).to_usize_addr(), Ok(0));
+        assert_eq!(Word::from_int(141_214_768_240).to_usize_addr(), Ok(141_214_768_240));
+        assert_eq!(Word::from_int(-5).to_usize_addr(), Err(MemError::OutOfBounds(-5)));
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut memory = VecMemory::new(4);
+        assert_eq!(memory.load(Word::from_int(4)), Err(MemError::OutOfBounds(4)));
+        assert_eq!(memory.load(Word::from_int(-1)), Err(MemError::OutOfBounds(-1)));
+        assert_eq!(memory.store(Word::from_int(100), Word::zero()), Err(MemError::OutOfBounds(100)));
+    }
+
+    #[test]
+    fn test_dump_memory() {
+        let program = [Word::from_int(7), Word::from_int(-13)];
+        let memory = VecMemory::with_program(&program, 3);
+        let dump = dump_memory(&memory, Word::zero(), 4);
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], format!("{:>16} {} {:>16}", 0, Word::from_int(7), 7));
+        assert_eq!(lines[1], format!("{:>16} {} {:>16}", 1, Word::from_int(-13), -13));
+        assert!(lines[2].trim_start().starts_with("2 "));
+        assert!(lines[2].ends_with(" 0"));
+        assert_eq!(lines[3], format!("{:>16} <address 3 is out of bounds>", 3));
+    }
+
+    #[test]
+    fn test_dump_memory_wraps() {
+        let memory = VecMemory::new(2);
+        let max = Word::from_int(141_214_768_240);
+        let dump = dump_memory(&memory, max, 3);
+        let addresses: Vec<&str> = dump.lines().map(|l| l.split_whitespace().next().unwrap()).collect();
+        assert_eq!(addresses, ["141214768240", "-141214768240", "-141214768239"]);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut memory = VecMemory::new(6);
+        memory.fill(Word::from_int(1), 3, Word::from_int(9)).unwrap();
+        let values: Vec<i64> = (0..6).map(|a| memory.load(Word::from_int(a)).unwrap().to_int()).collect();
+        assert_eq!(values, [0, 9, 9, 9, 0, 0]);
+
+        assert_eq!(memory.fill(Word::from_int(4), 3, Word::from_int(1)), Err(MemError::OutOfBounds(6)));
+        assert_eq!(memory.load(Word::from_int(4)).unwrap(), Word::zero());
+        assert_eq!(memory.fill(Word::from_int(-1), 1, Word::zero()), Err(MemError::OutOfBounds(-1)));
+    }
+
+    #[test]
+    fn test_overlapping_copy_within() {
+        let program: Vec<Word> = (1..=5).map(Word::from_int).collect();
+        let mut memory = VecMemory::with_program(&program, 6);
+        memory.copy_within(Word::from_int(0), Word::from_int(2), 3).unwrap();
+        let values: Vec<i64> = (0..6).map(|a| memory.load(Word::from_int(a)).unwrap().to_int()).collect();
+        assert_eq!(values, [1, 2, 1, 2, 3, 0]);
+
+        memory.copy_within(Word::from_int(2), Word::from_int(1), 3).unwrap();
+        let values: Vec<i64> = (0..6).map(|a| memory.load(Word::from_int(a)).unwrap().to_int()).collect();
+        assert_eq!(values, [1, 1, 2, 3, 3, 0]);
+
+        assert_eq!(
+            memory.copy_within(Word::from_int(0), Word::from_int(4), 3),
+            Err(MemError::OutOfBounds(6))
+        );
+    }
+
+    #[test]
+    fn test_with_program() {
+        let program = [Word::from_int(1), Word::from_int(2)];
+        let memory = VecMemory::with_program(&program, 8);
+        assert_eq!(memory.size(), 8);
+        assert_eq!(memory.load(Word::from_int(1)).unwrap().to_int(), 2);
+        assert_eq!(VecMemory::with_program(&program, 1).size(), 2);
+    }
+
+    #[test]
+    fn test_tryte_memory_aligned_words() {
+        let mut memory = TryteMemory::new(12);
+        let value = Word::from_int(-123_456_789);
+        memory.store_word(Word::from_int(4), value).unwrap();
+        assert_eq!(memory.load_word(Word::from_int(4)), Ok(value));
+        // Little endian, the lowest address holds the least significant tryte.
+        for (i, tryte) in value.to_trytes().into_iter().enumerate() {
+            assert_eq!(memory.load_tryte(Word::from_int(4 + i as i64)), Ok(tryte));
+        }
+        assert_eq!(memory.load_word(Word::zero()), Ok(Word::zero()));
+
+        memory.store_tryte(Word::from_int(8), Tryte::from_int(-5)).unwrap();
+        assert_eq!(memory.load_word(Word::from_int(8)).unwrap().to_int(), -5);
+    }
+
+    #[test]
+    fn test_tryte_memory_access_errors() {
+        let mut memory = TryteMemory::new(6);
+        assert_eq!(memory.load_word(Word::from_int(2)), Err(MemError::Misaligned(2)));
+        assert_eq!(memory.store_word(Word::from_int(1), Word::from_int(7)), Err(MemError::Misaligned(1)));
+        // The word at 4 would run past the last tryte, and nothing of it is written.
+        assert_eq!(memory.store_word(Word::from_int(4), Word::from_int(7)), Err(MemError::OutOfBounds(6)));
+        assert_eq!(memory.load_tryte(Word::from_int(4)), Ok(Tryte::zero()));
+        assert_eq!(memory.load_word(Word::from_int(-4)), Err(MemError::OutOfBounds(-4)));
+        assert_eq!(memory.load_tryte(Word::from_int(6)), Err(MemError::OutOfBounds(6)));
+    }
+}
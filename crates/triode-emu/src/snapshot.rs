@@ -0,0 +1,186 @@
+//! Copies of the architectural state of a `Cpu`, and compact deltas between them.
+
+use bternary::balanced_int::BalancedInt;
+use triode_arch::instruction_set::{MachineWord, Register, REGISTER_COUNT};
+use crate::cpu::Cpu;
+use crate::memory::{MemError, Memory};
+
+/// The registers, pc and memory of a CPU at one point in time.
+/// Trap, interrupt and cycle state isn't included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuSnapshot<const TRITS: usize = 24> {
+    pub registers: [BalancedInt<TRITS>; REGISTER_COUNT],
+    pub pc: BalancedInt<TRITS>,
+    pub memory: Vec<BalancedInt<TRITS>>,
+}
+
+/// What changed between two snapshots. Every entry keeps the old value next to the new one,
+/// so a delta can be inverted to step backwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDelta<const TRITS: usize = 24> {
+    /// `(register, old, new)` for each changed register.
+    pub registers: Vec<(Register, BalancedInt<TRITS>, BalancedInt<TRITS>)>,
+    /// `(old, new)` if the pc changed.
+    pub pc: Option<(BalancedInt<TRITS>, BalancedInt<TRITS>)>,
+    /// `(address, old, new)` for each changed memory word.
+    pub memory: Vec<(usize, BalancedInt<TRITS>, BalancedInt<TRITS>)>,
+}
+
+impl<const TRITS: usize> CpuSnapshot<TRITS> {
+    /// The changes that turn `self` into `other`.
+    /// Panics if the memories differ in size, which snapshots of one CPU never do.
+    pub fn diff(&self, other: &Self) -> SnapshotDelta<TRITS> {
+        assert_eq!(self.memory.len(), other.memory.len(), "snapshots have different memory sizes");
+        let registers = (0..REGISTER_COUNT)
+            .filter(|&i| self.registers[i] != other.registers[i])
+            .map(|i| (Register(i as u8), self.registers[i], other.registers[i]))
+            .collect();
+        let memory = self.memory.iter().zip(&other.memory).enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(addr, (&old, &new))| (addr, old, new))
+            .collect();
+        SnapshotDelta {
+            registers,
+            pc: (self.pc != other.pc).then_some((self.pc, other.pc)),
+            memory,
+        }
+    }
+
+    /// Writes the new values of `delta`. Panics if it addresses memory past the end.
+    pub fn apply_delta(&mut self, delta: &SnapshotDelta<TRITS>) {
+        for &(register, _, new) in &delta.registers {
+            self.registers[register.0 as usize] = new;
+        }
+        if let Some((_, new)) = delta.pc {
+            self.pc = new;
+        }
+        for &(addr, _, new) in &delta.memory {
+            self.memory[addr] = new;
+        }
+    }
+}
+
+impl<const TRITS: usize> SnapshotDelta<TRITS> {
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty() && self.pc.is_none() && self.memory.is_empty()
+    }
+
+    /// The delta undoing this one.
+    pub fn inverse(&self) -> SnapshotDelta<TRITS> {
+        SnapshotDelta {
+            registers: self.registers.iter().map(|&(r, old, new)| (r, new, old)).collect(),
+            pc: self.pc.map(|(old, new)| (new, old)),
+            memory: self.memory.iter().map(|&(a, old, new)| (a, new, old)).collect(),
+        }
+    }
+}
+
+impl<M: Memory<TRITS>, const TRITS: usize> Cpu<M, TRITS>
+where
+    BalancedInt<TRITS>: MachineWord,
+{
+    pub fn snapshot(&self) -> CpuSnapshot<TRITS> {
+        let memory = (0..self.memory().size())
+            .map(|addr| self.memory().load(BalancedInt::from_int(addr as i64)).expect("address below size"))
+            .collect();
+        CpuSnapshot {
+            registers: std::array::from_fn(|i| self.register(Register(i as u8))),
+            pc: self.pc(),
+            memory,
+        }
+    }
+
+    /// Loads the registers, pc and memory of `snapshot`.
+    /// Errors if the snapshot holds more memory than this CPU has.
+    pub fn restore(&mut self, snapshot: &CpuSnapshot<TRITS>) -> Result<(), MemError> {
+        for (addr, &word) in snapshot.memory.iter().enumerate() {
+            self.memory_mut().store(BalancedInt::from_int(addr as i64), word)?;
+        }
+        for (i, &value) in snapshot.registers.iter().enumerate() {
+            self.set_register(Register(i as u8), value);
+        }
+        self.set_pc(snapshot.pc);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bternary::Word;
+    use triode_asm::assemble;
+    use crate::memory::VecMemory;
+    use super::*;
+
+    fn cpu_for(source: &str) -> Cpu {
+        let program = assemble(source).unwrap();
+        Cpu::new(VecMemory::with_program(&program, 16))
+    }
+
+    #[test]
+    fn test_diff_captures_changes() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 7
+            store r0, r1, 12
+            halt
+        ");
+        let before = cpu.snapshot();
+        assert!(before.diff(&before).is_empty());
+        cpu.step().unwrap();
+        cpu.step().unwrap();
+        let after = cpu.snapshot();
+
+        let delta = before.diff(&after);
+        assert_eq!(delta.registers, [(Register(1), Word::zero(), Word::from_int(7))]);
+        assert_eq!(delta.pc, Some((Word::zero(), Word::from_int(2))));
+        assert_eq!(delta.memory, [(12, Word::zero(), Word::from_int(7))]);
+    }
+
+    #[test]
+    fn test_forward_then_inverse_round_trip() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 5
+            addi r2, r2, 3
+            store r1, r2, 5
+            addi r1, r1, -1
+            bne r1, r0, -3
+            halt
+        ");
+        let mut snapshots = vec![cpu.snapshot()];
+        while !cpu.is_halted() {
+            cpu.step().unwrap();
+            snapshots.push(cpu.snapshot());
+        }
+        let deltas: Vec<SnapshotDelta> = snapshots.windows(2).map(|w| w[0].diff(&w[1])).collect();
+
+        let mut state = snapshots[0].clone();
+        for delta in &deltas {
+            state.apply_delta(delta);
+        }
+        assert_eq!(&state, snapshots.last().unwrap());
+        for delta in deltas.iter().rev() {
+            state.apply_delta(&delta.inverse());
+        }
+        assert_eq!(state, snapshots[0]);
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut cpu = cpu_for("
+            addi r1, r0, 4
+            store r0, r1, 10
+            halt
+        ");
+        let start = cpu.snapshot();
+        cpu.run(10).unwrap();
+        let end = cpu.snapshot();
+
+        let mut replay = cpu_for("halt");
+        replay.restore(&start).unwrap();
+        assert_eq!(replay.snapshot(), start);
+        replay.restore(&end).unwrap();
+        assert_eq!(replay.snapshot(), end);
+
+        let mut small = Cpu::new(VecMemory::new(4));
+        assert_eq!(small.restore(&end), Err(MemError::OutOfBounds(4)));
+    }
+}
@@ -0,0 +1,24 @@
+use bternary::balanced_int::BalancedInt;
+use crate::memory::MemError;
+
+/// Why the CPU trapped, kept by the host side `Cpu::trap_cause` along with `Cpu::trap_pc`.
+/// Neither is written to a guest register, so a guest handler can't tell traps apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrapCause<const TRITS: usize = 24> {
+    DivisionByZero,
+    /// The word that failed to decode.
+    IllegalInstruction(BalancedInt<TRITS>),
+    /// A fetch, load or store outside of memory.
+    MemoryFault(MemError),
+}
+
+impl<const TRITS: usize> TrapCause<TRITS> {
+    /// Numeric cause code for the host, e.g. for logs or exit statuses. Stable across versions.
+    pub fn code(&self) -> i64 {
+        match self {
+            TrapCause::DivisionByZero => 1,
+            TrapCause::IllegalInstruction(_) => 2,
+            TrapCause::MemoryFault(_) => 3,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use bternary::Word;
+use triode_arch::instruction_set::{Register, SP};
+use triode_asm::assemble;
+use triode_emu::{Cpu, Memory, VecMemory};
+
+#[test]
+fn test_call_subroutine_and_return() {
+    let program = assemble("
+        addi r2, r0, 32     ; sp starts at the top of memory
+        addi r3, r0, 4
+        call 4              ; -> square
+        addi r5, r4, 1      ; runs after the return
+        halt
+        nop
+        mul r4, r3, r3      ; square: r4 = r3 * r3
+        ret
+    ").unwrap();
+    let mut cpu = Cpu::new(VecMemory::with_program(&program, 32));
+
+    cpu.run(100).unwrap();
+
+    assert!(cpu.is_halted());
+    assert_eq!(cpu.register(Register(4)).to_int(), 16);
+    assert_eq!(cpu.register(Register(5)).to_int(), 17);
+    assert_eq!(cpu.pc().to_int(), 4);
+    // The stack is balanced again and the return address is left below it.
+    assert_eq!(cpu.register(SP).to_int(), 32);
+    assert_eq!(cpu.memory().load(Word::from_int(31)).unwrap().to_int(), 3);
+}
+
+#[test]
+fn test_nested_calls() {
+    let program = assemble("
+        addi r2, r0, 32
+        call 2              ; -> outer
+        halt
+        addi r3, r3, 1      ; outer
+        call 2              ; -> inner
+        ret
+        addi r3, r3, 10     ; inner
+        ret
+    ").unwrap();
+    let mut cpu = Cpu::new(VecMemory::with_program(&program, 32));
+
+    cpu.run(100).unwrap();
+
+    assert!(cpu.is_halted());
+    assert_eq!(cpu.register(Register(3)).to_int(), 11);
+    assert_eq!(cpu.register(SP).to_int(), 32);
+}